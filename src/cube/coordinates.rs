@@ -1,7 +1,7 @@
 //! various coordinates representations
 //! each with their pros and cons
 use enum_iterator::IntoEnumIterator;
-use super::sizes::{NB_SQUARES_FACE, NB_SQUARES_SIDE};
+use super::sizes::NB_SQUARES_SIDE;
 use super::moves::{MoveKind, MoveDescription};
 
 //-----------------------------------------------------------------------------
@@ -10,8 +10,9 @@ use super::moves::{MoveKind, MoveDescription};
 /// coordinates into a 1D array
 /// this format leads to very efficient implementations
 /// but is impractical to manipulata as the mapping to the cube is non-trivial
+/// SIDE is the number of squares on the side of the cube
 #[derive(Clone, Copy)]
-pub struct Coordinate1D
+pub struct Coordinate1D<const SIDE: usize = NB_SQUARES_SIDE>
 {
     /// 0 to NB_SQUARES_CUBE-1
     pub x: usize
@@ -24,30 +25,48 @@ impl Coordinate1D
     {
         Coordinate1D { x }
     }
+}
 
+impl<const SIDE: usize> Coordinate1D<SIDE>
+{
     /// converts to 2D coordinates
-    fn to_2D(self) -> Coordinate2D
+    pub fn to_2D(self) -> Coordinate2D<SIDE>
     {
-        let face = Face::from_usize(self.x / NB_SQUARES_FACE);
-        let x = (self.x % NB_SQUARES_FACE) / NB_SQUARES_SIDE;
-        let y = self.x % NB_SQUARES_SIDE;
+        let nb_squares_face = SIDE * SIDE;
+        let face = Face::from_usize(self.x / nb_squares_face);
+        let x = (self.x % nb_squares_face) / SIDE;
+        let y = self.x % SIDE;
         Coordinate2D { face, x, y }
     }
 
     /// converts to 3D coordinates
-    fn to_3D(self) -> Coordinate3D
+    fn to_3D(self) -> Coordinate3D<SIDE>
     {
         self.to_2D().to_3D()
     }
 
+    /// returns the 3D position (left_right, down_up, front_back) of the piece holding the square
+    /// squares belonging to the same piece share the same position
+    pub fn piece_position(self) -> [usize; 3]
+    {
+        let coordinates = self.to_3D();
+        [coordinates.left_right, coordinates.down_up, coordinates.front_back]
+    }
+
     /// rotates along the given axis
-    pub fn rotate(&self, axis: RotationAxis) -> Coordinate1D
+    pub fn rotate(&self, axis: RotationAxis) -> Coordinate1D<SIDE>
     {
         self.to_3D().rotate(axis).to_1D()
     }
 
+    /// reflects through the plane separating the Left and Right faces
+    pub fn mirror(&self) -> Coordinate1D<SIDE>
+    {
+        self.to_3D().mirror().to_1D()
+    }
+
     /// takes a move and produces new, rotated, coordinates by applying the move
-    pub fn apply_move(&self, m: &MoveDescription) -> Coordinate1D
+    pub fn apply_move(&self, m: &MoveDescription) -> Coordinate1D<SIDE>
     {
         self.to_3D().apply_move(m).to_1D()
     }
@@ -57,7 +76,7 @@ impl Coordinate1D
 // 2D + face
 
 /// all faces of a cube
-#[derive(Clone, Copy, PartialEq, IntoEnumIterator)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoEnumIterator)]
 #[repr(usize)]
 pub enum Face
 {
@@ -77,12 +96,33 @@ impl Face
         debug_assert!(n < 6);
         unsafe { ::std::mem::transmute(n) }
     }
+
+    /// returns the face on the other side of the cube
+    pub fn opposite(self) -> Face
+    {
+        match self
+        {
+            Face::Left => Face::Right,
+            Face::Right => Face::Left,
+            Face::Front => Face::Back,
+            Face::Back => Face::Front,
+            Face::Up => Face::Down,
+            Face::Down => Face::Up
+        }
+    }
 }
 
 /// coordinates into a 2D array with additional face coordinate
 /// it is easy to identify classical position (center and corners) in this format
 /// it is also easy to convert it to 3D and 1D
-pub struct Coordinate2D
+///
+/// each face is seen from outside the cube, x going from left to right and y from bottom to top:
+/// - Left, Front, Right and Back are seen with the Up face above them
+/// - Up is seen with the Front face below it
+/// - Down is seen with the Front face above it
+///
+/// this is the layout of the faces once the cube is unfolded into a cross with Front at its center
+pub struct Coordinate2D<const SIDE: usize = NB_SQUARES_SIDE>
 {
     /// in order [Left, Front, Right, Back, Up, Down]
     pub face: Face,
@@ -92,17 +132,17 @@ pub struct Coordinate2D
     pub y: usize
 }
 
-impl Coordinate2D
+impl<const SIDE: usize> Coordinate2D<SIDE>
 {
     /// converts into 1D coordinates
-    pub fn to_1D(&self) -> Coordinate1D
+    pub fn to_1D(&self) -> Coordinate1D<SIDE>
     {
-        let x = (self.face as usize) * NB_SQUARES_FACE + self.x * NB_SQUARES_SIDE + self.y;
+        let x = (self.face as usize) * SIDE * SIDE + self.x * SIDE + self.y;
         Coordinate1D { x }
     }
 
     /// converts into 3D coordinates
-    fn to_3D(&self) -> Coordinate3D
+    fn to_3D(&self) -> Coordinate3D<SIDE>
     {
         match self.face
         {
@@ -110,14 +150,14 @@ impl Coordinate2D
             {
                 let axis = RotationAxis::LeftRight;
                 let left_right = 0;
-                let front_back = (NB_SQUARES_SIDE - 1) - self.x;
+                let front_back = (SIDE - 1) - self.x;
                 let down_up = self.y;
                 Coordinate3D { left_right, down_up, front_back, axis }
             }
             Face::Right =>
             {
                 let axis = RotationAxis::LeftRight;
-                let left_right = 2;
+                let left_right = SIDE - 1;
                 let front_back = self.x;
                 let down_up = self.y;
                 Coordinate3D { left_right, down_up, front_back, axis }
@@ -133,8 +173,8 @@ impl Coordinate2D
             Face::Back =>
             {
                 let axis = RotationAxis::FrontBack;
-                let front_back = 2;
-                let left_right = (NB_SQUARES_SIDE - 1) - self.x;
+                let front_back = SIDE - 1;
+                let left_right = (SIDE - 1) - self.x;
                 let down_up = self.y;
                 Coordinate3D { left_right, down_up, front_back, axis }
             }
@@ -143,13 +183,13 @@ impl Coordinate2D
                 let axis = RotationAxis::DownUp;
                 let down_up = 0;
                 let left_right = self.x;
-                let front_back = (NB_SQUARES_SIDE - 1) - self.y;
+                let front_back = (SIDE - 1) - self.y;
                 Coordinate3D { left_right, down_up, front_back, axis }
             }
             Face::Up =>
             {
                 let axis = RotationAxis::DownUp;
-                let down_up = 2;
+                let down_up = SIDE - 1;
                 let left_right = self.x;
                 let front_back = self.y;
                 Coordinate3D { left_right, down_up, front_back, axis }
@@ -162,7 +202,7 @@ impl Coordinate2D
 // 3D + axis
 
 /// axis along which a rotation can be done
-#[derive(Clone, Copy, IntoEnumIterator, Debug)]
+#[derive(Clone, Copy, IntoEnumIterator, Debug, PartialEq, Eq)]
 pub enum RotationAxis
 {
     LeftRight,
@@ -174,7 +214,7 @@ pub enum RotationAxis
 /// this format is very easy to rotate correctly
 /// one downside of this format is that some position do not correspond to a square on the cube
 #[derive(Clone)]
-pub struct Coordinate3D
+pub struct Coordinate3D<const SIDE: usize = NB_SQUARES_SIDE>
 {
     /// right to left
     /// 0 to NB_SQUARES_SIDE-1
@@ -197,26 +237,29 @@ impl Coordinate3D
     {
         Coordinate3D { left_right, down_up, front_back, axis }
     }
+}
 
+impl<const SIDE: usize> Coordinate3D<SIDE>
+{
     /// converts into 1D coordinates
-    pub fn to_1D(&self) -> Coordinate1D
+    pub fn to_1D(&self) -> Coordinate1D<SIDE>
     {
         self.to_2D().to_1D()
     }
 
     /// converts into 2D coordinates
-    pub fn to_2D(&self) -> Coordinate2D
+    pub fn to_2D(&self) -> Coordinate2D<SIDE>
     {
         match self.axis
         {
             RotationAxis::LeftRight if self.left_right == 0 =>
             {
                 let face = Face::Left;
-                let x = (NB_SQUARES_SIDE - 1) - self.front_back;
+                let x = (SIDE - 1) - self.front_back;
                 let y = self.down_up;
                 Coordinate2D { face, x, y }
             }
-            RotationAxis::LeftRight if self.left_right == 2 =>
+            RotationAxis::LeftRight if self.left_right == SIDE - 1 =>
             {
                 let face = Face::Right;
                 let x = self.front_back;
@@ -230,10 +273,10 @@ impl Coordinate3D
                 let y = self.down_up;
                 Coordinate2D { face, x, y }
             }
-            RotationAxis::FrontBack if self.front_back == 2 =>
+            RotationAxis::FrontBack if self.front_back == SIDE - 1 =>
             {
                 let face = Face::Back;
-                let x = (NB_SQUARES_SIDE - 1) - self.left_right;
+                let x = (SIDE - 1) - self.left_right;
                 let y = self.down_up;
                 Coordinate2D { face, x, y }
             }
@@ -241,10 +284,10 @@ impl Coordinate3D
             {
                 let face = Face::Down;
                 let x = self.left_right;
-                let y = (NB_SQUARES_SIDE - 1) - self.front_back;
+                let y = (SIDE - 1) - self.front_back;
                 Coordinate2D { face, x, y }
             }
-            RotationAxis::DownUp if self.down_up == 2 =>
+            RotationAxis::DownUp if self.down_up == SIDE - 1 =>
             {
                 let face = Face::Up;
                 let x = self.left_right;
@@ -259,7 +302,7 @@ impl Coordinate3D
     }
 
     /// does a 90° clockwise rotation along the LeftRight axis
-    fn rotate_left_right(&self) -> Coordinate3D
+    fn rotate_left_right(&self) -> Coordinate3D<SIDE>
     {
         // change the axis
         let axis = match self.axis
@@ -270,13 +313,13 @@ impl Coordinate3D
         };
         // rotates the coordinates
         let left_right = self.left_right;
-        let down_up = (NB_SQUARES_SIDE - 1) - self.front_back;
+        let down_up = (SIDE - 1) - self.front_back;
         let front_back = self.down_up;
         Coordinate3D { left_right, down_up, front_back, axis }
     }
 
     /// does a 90° clockwise rotation along the DownUp axis
-    fn rotate_down_up(&self) -> Coordinate3D
+    fn rotate_down_up(&self) -> Coordinate3D<SIDE>
     {
         // change the axis
         let axis = match self.axis
@@ -286,14 +329,14 @@ impl Coordinate3D
             RotationAxis::FrontBack => RotationAxis::LeftRight
         };
         // rotates the coordinates
-        let left_right = (NB_SQUARES_SIDE - 1) - self.front_back;
+        let left_right = (SIDE - 1) - self.front_back;
         let down_up = self.down_up;
         let front_back = self.left_right;
         Coordinate3D { left_right, down_up, front_back, axis }
     }
 
     /// does a 90° clockwise rotation along the FrontBack axis
-    fn rotate_front_back(&self) -> Coordinate3D
+    fn rotate_front_back(&self) -> Coordinate3D<SIDE>
     {
         // change the axis
         let axis = match self.axis
@@ -303,14 +346,14 @@ impl Coordinate3D
             RotationAxis::FrontBack => RotationAxis::FrontBack
         };
         // rotates the coordinates
-        let left_right = (NB_SQUARES_SIDE - 1) - self.down_up;
+        let left_right = (SIDE - 1) - self.down_up;
         let down_up = self.left_right;
         let front_back = self.front_back;
         Coordinate3D { left_right, down_up, front_back, axis }
     }
 
     /// does a 90° clockwise rotation along the given axis
-    fn rotate(&self, axis: RotationAxis) -> Coordinate3D
+    fn rotate(&self, axis: RotationAxis) -> Coordinate3D<SIDE>
     {
         match axis
         {
@@ -320,37 +363,42 @@ impl Coordinate3D
         }
     }
 
+    /// reflects through the plane separating the Left and Right faces
+    fn mirror(&self) -> Coordinate3D<SIDE>
+    {
+        let mut coordinates = self.clone();
+        coordinates.left_right = (SIDE - 1) - self.left_right;
+        coordinates
+    }
+
     /// returns true if the coordinates should be impacted by the given move
     /// as a function of the slice of the cube that is rotated by the move
+    /// NOTE: center layers only exist when the side has an odd number of squares
     fn should_move(&self, kind: MoveKind) -> bool
     {
+        let center = SIDE / 2;
         match kind
         {
             MoveKind::Front => self.front_back == 0,
-            MoveKind::Side => self.front_back == 1,
-            MoveKind::Back => self.front_back == 2,
+            MoveKind::Side => self.front_back == center,
+            MoveKind::Back => self.front_back == SIDE - 1,
             MoveKind::Left => self.left_right == 0,
-            MoveKind::Middle => self.left_right == 1,
-            MoveKind::Right => self.left_right == 2,
+            MoveKind::Middle => self.left_right == center,
+            MoveKind::Right => self.left_right == SIDE - 1,
             MoveKind::Down => self.down_up == 0,
-            MoveKind::Equator => self.down_up == 1,
-            MoveKind::Up => self.down_up == 2
+            MoveKind::Equator => self.down_up == center,
+            MoveKind::Up => self.down_up == SIDE - 1
         }
     }
 
     /// takes a move and produces new, rotated, coordinates by applying the move
-    fn apply_move(&self, m: &MoveDescription) -> Coordinate3D
+    fn apply_move(&self, m: &MoveDescription) -> Coordinate3D<SIDE>
     {
         let mut coordinates = self.clone();
         if coordinates.should_move(m.kind)
         {
             // axis along which the rotation will be done
-            let axis = match m.kind
-            {
-                MoveKind::Front | MoveKind::Side | MoveKind::Back => RotationAxis::FrontBack,
-                MoveKind::Right | MoveKind::Middle | MoveKind::Left => RotationAxis::LeftRight,
-                MoveKind::Down | MoveKind::Equator | MoveKind::Up => RotationAxis::DownUp
-            };
+            let axis = m.kind.axis();
             // does 90° clockwise rotations until the desired amplitude is reached
             for _rotation in 0..m.amplitude.nb_rotations()
             {
@@ -0,0 +1,417 @@
+//! Representation of a cube as a set of pieces (cubies)
+//!
+//! Corners and middles are described by a permutation and an orientation.
+//! Everything is expressed relative to the center squares: a slot is named after the colors of the centers it touches
+//! so that whole-cube rotations (and center layer moves) do not change the representation.
+//!
+//! We follow the conventions of Kociemba's two-phase solver for the order of the slots
+//! and the order of the squares of each slot (clockwise, starting with the Up or Down face):
+//! http://kociemba.org/cube.htm
+use enum_iterator::IntoEnumIterator;
+use rand::{Rng, seq::SliceRandom};
+use super::Cube;
+use super::color::{Color, NB_COLORS};
+use super::sizes::NB_SQUARES_CUBE;
+use super::coordinates::{Face, Coordinate3D, RotationAxis};
+
+//-----------------------------------------------------------------------------
+// Constants
+
+/// number of corners
+pub const NB_CORNERS: usize = 8;
+/// number of possible orientations for a corner
+pub const NB_CORNER_ORIENTATIONS: usize = 3;
+/// number of middles
+pub const NB_MIDDLES: usize = 12;
+/// number of possible orientations for a middle
+pub const NB_MIDDLE_ORIENTATIONS: usize = 2;
+
+/// faces touched by each corner slot (when the cube is in the reference orientation)
+/// in clockwise order, starting with the Up or Down face
+pub const CORNER_SLOTS: [[Face; 3]; NB_CORNERS] = [[Face::Up, Face::Right, Face::Front],
+                                                   [Face::Up, Face::Front, Face::Left],
+                                                   [Face::Up, Face::Left, Face::Back],
+                                                   [Face::Up, Face::Back, Face::Right],
+                                                   [Face::Down, Face::Front, Face::Right],
+                                                   [Face::Down, Face::Left, Face::Front],
+                                                   [Face::Down, Face::Back, Face::Left],
+                                                   [Face::Down, Face::Right, Face::Back]];
+
+/// faces touched by each middle slot (when the cube is in the reference orientation)
+/// starting with the Up or Down face when there is one, the Front or Back face otherwise
+pub const MIDDLE_SLOTS: [[Face; 2]; NB_MIDDLES] = [[Face::Up, Face::Right],
+                                                   [Face::Up, Face::Front],
+                                                   [Face::Up, Face::Left],
+                                                   [Face::Up, Face::Back],
+                                                   [Face::Down, Face::Right],
+                                                   [Face::Down, Face::Front],
+                                                   [Face::Down, Face::Left],
+                                                   [Face::Down, Face::Back],
+                                                   [Face::Front, Face::Right],
+                                                   [Face::Front, Face::Left],
+                                                   [Face::Back, Face::Left],
+                                                   [Face::Back, Face::Right]];
+
+//-----------------------------------------------------------------------------
+// Cubies
+
+/// a Rubik's cube stored as the permutation and orientation of its pieces
+/// the piece `i` is the piece that belongs to the slot `i` once the cube is solved
+/// an orientation of 0 means that the first square of the piece is on the first square of the slot
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cubies
+{
+    /// index of the corner in each corner slot
+    pub corner_permutation: [u8; NB_CORNERS],
+    /// orientation (0 to NB_CORNER_ORIENTATIONS-1) of the corner in each corner slot
+    pub corner_orientation: [u8; NB_CORNERS],
+    /// index of the middle in each middle slot
+    pub middle_permutation: [u8; NB_MIDDLES],
+    /// orientation (0 to NB_MIDDLE_ORIENTATIONS-1) of the middle in each middle slot
+    pub middle_orientation: [u8; NB_MIDDLES]
+}
+
+impl Cubies
+{
+    /// produces the pieces of a solved cube
+    pub fn solved() -> Cubies
+    {
+        let mut corner_permutation = [0; NB_CORNERS];
+        for (i, corner) in corner_permutation.iter_mut().enumerate()
+        {
+            *corner = i as u8;
+        }
+        let mut middle_permutation = [0; NB_MIDDLES];
+        for (i, middle) in middle_permutation.iter_mut().enumerate()
+        {
+            *middle = i as u8;
+        }
+        let corner_orientation = [0; NB_CORNERS];
+        let middle_orientation = [0; NB_MIDDLES];
+        Cubies { corner_permutation, corner_orientation, middle_permutation, middle_orientation }
+    }
+
+    /// produces the pieces of a random, solvable, cube
+    /// all solvable cubes have the same probability of being produced
+    pub fn random<R: Rng>(rng: &mut R) -> Cubies
+    {
+        let mut cubies = Cubies::solved();
+        // random permutations
+        cubies.corner_permutation.shuffle(rng);
+        cubies.middle_permutation.shuffle(rng);
+        // both permutations need to have the same parity
+        if permutation_parity(&cubies.corner_permutation) != permutation_parity(&cubies.middle_permutation)
+        {
+            cubies.middle_permutation.swap(0, 1);
+        }
+        // random orientations, the last orientation is given by the others
+        for orientation in cubies.corner_orientation.iter_mut().take(NB_CORNERS - 1)
+        {
+            *orientation = rng.gen_range(0..NB_CORNER_ORIENTATIONS as u8);
+        }
+        cubies.corner_orientation[NB_CORNERS - 1] = 0;
+        let nb_orientations = NB_CORNER_ORIENTATIONS as u8;
+        cubies.corner_orientation[NB_CORNERS - 1] = (nb_orientations - cubies.corner_twist()) % nb_orientations;
+        for orientation in cubies.middle_orientation.iter_mut().take(NB_MIDDLES - 1)
+        {
+            *orientation = rng.gen_range(0..NB_MIDDLE_ORIENTATIONS as u8);
+        }
+        cubies.middle_orientation[NB_MIDDLES - 1] = 0;
+        cubies.middle_orientation[NB_MIDDLES - 1] = cubies.middle_flip();
+        cubies
+    }
+
+    /// extracts the pieces of a cube
+    /// returns None if the centers are not all of different colors
+    /// or if a corner or middle does not correspond to an actual piece
+    pub fn from_cube(cube: &Cube) -> Option<Cubies>
+    {
+        let face_of_color = face_of_color(cube)?;
+        let mut cubies = Cubies::solved();
+
+        // corners
+        for (slot, slot_faces) in CORNER_SLOTS.iter().enumerate()
+        {
+            let faces = slot_faces.map(|face| face_of_color[face as usize]);
+            let colors = faces.map(|face| cube.squares[square_index(&faces, face)]);
+            let mask = colors.iter().fold(0, |mask, color| mask | color_mask(*color));
+            let piece = CORNER_SLOTS.iter().position(|piece_faces| faces_mask(piece_faces) == mask)?;
+            // the orientation is the index of the square holding the first color of the piece
+            let piece_colors = CORNER_SLOTS[piece].map(|face| face as usize);
+            let orientation = colors.iter().position(|color| *color as usize == piece_colors[0])?;
+            // checks that the colors are in clockwise order (otherwise this is not a real corner)
+            if colors[(orientation + 1) % 3] as usize != piece_colors[1]
+            {
+                return None;
+            }
+            cubies.corner_permutation[slot] = piece as u8;
+            cubies.corner_orientation[slot] = orientation as u8;
+        }
+
+        // middles
+        for (slot, slot_faces) in MIDDLE_SLOTS.iter().enumerate()
+        {
+            let faces = slot_faces.map(|face| face_of_color[face as usize]);
+            let colors = faces.map(|face| cube.squares[square_index(&faces, face)]);
+            let mask = colors.iter().fold(0, |mask, color| mask | color_mask(*color));
+            let piece = MIDDLE_SLOTS.iter().position(|piece_faces| faces_mask(piece_faces) == mask)?;
+            let first_color = MIDDLE_SLOTS[piece][0] as usize;
+            let orientation = colors.iter().position(|color| *color as usize == first_color)?;
+            cubies.middle_permutation[slot] = piece as u8;
+            cubies.middle_orientation[slot] = orientation as u8;
+        }
+
+        Some(cubies)
+    }
+
+    /// turns the pieces into a cube in the reference orientation
+    /// (the one of `Cube::solved`)
+    pub fn to_cube(&self) -> Cube
+    {
+        let face_of_color = [Face::Left, Face::Front, Face::Right, Face::Back, Face::Up, Face::Down];
+        self.to_cube_oriented(&face_of_color)
+    }
+
+    /// turns the pieces into a cube where the center of color `c` is on the face `face_of_color[c]`
+    pub fn to_cube_oriented(&self, face_of_color: &[Face; NB_COLORS]) -> Cube
+    {
+        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+
+        // centers
+        for color in Color::ALL
+        {
+            let face = face_of_color[color as usize];
+            let faces = [face];
+            squares[square_index(&faces, face)] = color;
+        }
+
+        // corners
+        for (slot, slot_faces) in CORNER_SLOTS.iter().enumerate()
+        {
+            let faces = slot_faces.map(|face| face_of_color[face as usize]);
+            let piece = self.corner_permutation[slot] as usize;
+            let orientation = self.corner_orientation[slot] as usize;
+            for (i, piece_face) in CORNER_SLOTS[piece].iter().enumerate()
+            {
+                let face = faces[(i + orientation) % 3];
+                squares[square_index(&faces, face)] = Color::ALL[*piece_face as usize];
+            }
+        }
+
+        // middles
+        for (slot, slot_faces) in MIDDLE_SLOTS.iter().enumerate()
+        {
+            let faces = slot_faces.map(|face| face_of_color[face as usize]);
+            let piece = self.middle_permutation[slot] as usize;
+            let orientation = self.middle_orientation[slot] as usize;
+            for (i, piece_face) in MIDDLE_SLOTS[piece].iter().enumerate()
+            {
+                let face = faces[(i + orientation) % 2];
+                squares[square_index(&faces, face)] = Color::ALL[*piece_face as usize];
+            }
+        }
+
+        Cube { squares }
+    }
+
+    /// sum of the corner orientations modulo 3
+    /// it is 0 for all solvable cubes
+    pub fn corner_twist(&self) -> u8
+    {
+        self.corner_orientation.iter().sum::<u8>() % (NB_CORNER_ORIENTATIONS as u8)
+    }
+
+    /// sum of the middle orientations modulo 2
+    /// it is 0 for all solvable cubes
+    pub fn middle_flip(&self) -> u8
+    {
+        self.middle_orientation.iter().sum::<u8>() % (NB_MIDDLE_ORIENTATIONS as u8)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Helpers
+
+/// returns true if the permutation is odd
+/// the permutation is expected to contain all numbers from 0 to its length (excluded)
+pub fn permutation_parity(permutation: &[u8]) -> bool
+{
+    // counts the number of inversions
+    let mut is_odd = false;
+    for (i, x) in permutation.iter().enumerate()
+    {
+        for y in permutation.iter().skip(i + 1)
+        {
+            if x > y
+            {
+                is_odd = !is_odd;
+            }
+        }
+    }
+    is_odd
+}
+
+/// returns the face on which the center of each color is currently placed
+/// returns None if several centers share the same color
+fn face_of_color(cube: &Cube) -> Option<[Face; NB_COLORS]>
+{
+    let mut face_of_color = [None; NB_COLORS];
+    for face in Face::into_enum_iter()
+    {
+        let color = cube.get(face, 1, 1);
+        if color == Color::Invalid || face_of_color[color as usize].is_some()
+        {
+            return None;
+        }
+        face_of_color[color as usize] = Some(face);
+    }
+    Some(face_of_color.map(|face| face.unwrap()))
+}
+
+/// returns a mask with one bit set per color
+fn color_mask(color: Color) -> usize
+{
+    1 << (color as usize)
+}
+
+/// returns a mask with one bit set per color of the given faces (in the reference orientation)
+fn faces_mask(faces: &[Face]) -> usize
+{
+    faces.iter().fold(0, |mask, face| mask | (1 << (*face as usize)))
+}
+
+/// returns the 1D index of the square, on the given face, of the piece touching all the given faces
+fn square_index(faces: &[Face], face: Face) -> usize
+{
+    // 3D coordinates of the piece, 1 along the axis where it does not touch a face
+    let mut left_right = 1;
+    let mut down_up = 1;
+    let mut front_back = 1;
+    for f in faces
+    {
+        match f
+        {
+            Face::Left => left_right = 0,
+            Face::Right => left_right = 2,
+            Face::Down => down_up = 0,
+            Face::Up => down_up = 2,
+            Face::Front => front_back = 0,
+            Face::Back => front_back = 2
+        }
+    }
+    let axis = match face
+    {
+        Face::Left | Face::Right => RotationAxis::LeftRight,
+        Face::Down | Face::Up => RotationAxis::DownUp,
+        Face::Front | Face::Back => RotationAxis::FrontBack
+    };
+    Coordinate3D::new(left_right, down_up, front_back, axis).to_1D().x
+}
+
+//-----------------------------------------------------------------------------
+// Validity
+
+/// reasons for which a cube might be impossible to solve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidCubeReason
+{
+    /// the centers are not placed as in a solved cube (duplicated colors or mirrored cube)
+    InvalidCenters,
+    /// a corner or middle does not correspond to an actual piece
+    InvalidPiece,
+    /// a piece appears several times (and, thus, another piece is missing)
+    DuplicatedPiece,
+    /// the sum of the corner orientations is not a multiple of 3 (a single corner has been twisted)
+    CornerTwist,
+    /// the sum of the middle orientations is not a multiple of 2 (a single middle has been flipped)
+    MiddleFlip,
+    /// the corner and middle permutations have different parities (two pieces have been swapped)
+    PermutationParity
+}
+
+impl std::fmt::Display for InvalidCubeReason
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        let explanation = match self
+        {
+            InvalidCubeReason::InvalidCenters => "the centers do not match the ones of a solved cube",
+            InvalidCubeReason::InvalidPiece => "a corner or middle does not correspond to an actual piece",
+            InvalidCubeReason::DuplicatedPiece => "a piece appears more than once",
+            InvalidCubeReason::CornerTwist => "a corner has been twisted",
+            InvalidCubeReason::MiddleFlip => "a middle has been flipped",
+            InvalidCubeReason::PermutationParity => "two pieces have been swapped"
+        };
+        write!(formater, "invalid cube: {}", explanation)
+    }
+}
+
+impl std::error::Error for InvalidCubeReason {}
+
+/// returns true if the permutation contains all numbers from 0 to its length (excluded)
+fn is_permutation(permutation: &[u8]) -> bool
+{
+    let mut is_seen = vec![false; permutation.len()];
+    for x in permutation.iter().map(|x| *x as usize)
+    {
+        if is_seen[x]
+        {
+            return false;
+        }
+        is_seen[x] = true;
+    }
+    true
+}
+
+//-----------------------------------------------------------------------------
+// Cube
+
+impl Cube
+{
+    /// checks whether the cube can be solved
+    /// returns the invariant that is broken otherwise
+    pub fn is_valid(&self) -> Result<(), InvalidCubeReason>
+    {
+        // the centers should match the centers of one of the solved cubes
+        let centers = |cube: &Cube| Face::into_enum_iter().map(|face| cube.get(face, 1, 1)).collect::<Vec<Color>>();
+        let are_centers_valid = Cube::solved_cubes().iter().any(|solved| centers(solved) == centers(self));
+        if !are_centers_valid
+        {
+            return Err(InvalidCubeReason::InvalidCenters);
+        }
+
+        // all pieces should be actual pieces, appearing once
+        let cubies = Cubies::from_cube(self).ok_or(InvalidCubeReason::InvalidPiece)?;
+        if !is_permutation(&cubies.corner_permutation) || !is_permutation(&cubies.middle_permutation)
+        {
+            return Err(InvalidCubeReason::DuplicatedPiece);
+        }
+
+        // invariants preserved by all moves
+        if cubies.corner_twist() != 0
+        {
+            return Err(InvalidCubeReason::CornerTwist);
+        }
+        if cubies.middle_flip() != 0
+        {
+            return Err(InvalidCubeReason::MiddleFlip);
+        }
+        if permutation_parity(&cubies.corner_permutation) != permutation_parity(&cubies.middle_permutation)
+        {
+            return Err(InvalidCubeReason::PermutationParity);
+        }
+        Ok(())
+    }
+
+    /// produces a uniformly random, solvable, cube
+    /// unlike `Cube::scrambled` this is not limited to cubes close to a solved cube
+    pub fn random_state() -> Cube
+    {
+        let mut rng = rand::thread_rng();
+        // selects an orientation at random
+        let orientation = Cube::solved_cubes().choose(&mut rng).unwrap();
+        let face_of_color = face_of_color(orientation).unwrap();
+        // fills it with random pieces
+        Cubies::random(&mut rng).to_cube_oriented(&face_of_color)
+    }
+}
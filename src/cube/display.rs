@@ -1,28 +1,118 @@
 //! functions used for display
+use std::io::{self, Write, stdout};
 use ansi_term::{Style, Colour};
-use super::Cube;
-use super::color::Color;
+use super::CubeOfSize;
+use super::color::{Color, NB_COLORS};
 use super::coordinates::Face;
-use super::sizes::NB_SQUARES_SIDE;
 
-impl Color
+//-----------------------------------------------------------------------------
+// Color scheme
+
+/// a color as (red, green, blue)
+pub type Rgb = (u8, u8, u8);
+
+/// the RGB values used to display each color of the cube
+/// NOTE: colors are named after the western color scheme, a scheme can display them differently
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorScheme
+{
+    /// RGB of each color, in `Color::ALL` order
+    pub colors: [Rgb; NB_COLORS],
+    /// RGB used to display `Color::Invalid`
+    pub invalid: Rgb
+}
+
+impl ColorScheme
 {
+    /// the western color scheme: https://www.speedsolving.com/wiki/index.php/Western_Color_Scheme
+    /// uses the standard xterm values for the non-orange colors
+    pub fn western() -> ColorScheme
+    {
+        let colors = [(255, 127, 80), (0, 205, 0), (205, 0, 0), (0, 0, 238), (229, 229, 229), (205, 205, 0)];
+        ColorScheme { colors, invalid: (255, 0, 255) }
+    }
+
+    /// the japanese color scheme, in which blue is opposite to white and yellow to green
+    pub fn japanese() -> ColorScheme
+    {
+        let western = Self::western();
+        let mut colors = western.colors;
+        colors.swap(Color::Blue as usize, Color::Yellow as usize);
+        ColorScheme { colors, invalid: western.invalid }
+    }
+
+    /// a palette that stays distinguishable with red-green colorblindness
+    /// based on the Okabe-Ito palette: https://jfly.uni-koeln.de/color/
+    pub fn colorblind() -> ColorScheme
+    {
+        let colors =
+            [(230, 159, 0), (0, 158, 115), (213, 94, 0), (0, 114, 178), (255, 255, 255), (240, 228, 66)];
+        ColorScheme { colors, invalid: (0, 0, 0) }
+    }
+
+    /// returns the RGB used to display the given color
+    pub fn rgb(&self, color: Color) -> Rgb
+    {
+        match color
+        {
+            Color::Invalid => self.invalid,
+            color => self.colors[color as usize]
+        }
+    }
+
     /// converts the color to a shell color for display
+    pub fn shell_color(&self, color: Color) -> Colour
+    {
+        let (r, g, b) = self.rgb(color);
+        Colour::RGB(r, g, b)
+    }
+}
+
+/// the way a cube is rendered as text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayMode
+{
+    /// colored squares, using ANSI escape codes
+    Ansi,
+    /// one letter per square (the first letter of its color), for outputs that are not terminals
+    Ascii
+}
+
+impl Default for ColorScheme
+{
+    fn default() -> Self
+    {
+        Self::western()
+    }
+}
+
+impl Color
+{
+    /// converts the color to a shell color for display, using the western color scheme
     pub fn to_shell_color(self) -> Colour
+    {
+        ColorScheme::western().shell_color(self)
+    }
+
+    /// converts the color to a single letter for plain text display
+    pub fn to_letter(self) -> char
     {
         match self
         {
-            Color::Orange => Colour::RGB(255, 127, 80),
-            Color::Green => Colour::Green,
-            Color::Red => Colour::Red,
-            Color::Blue => Colour::Blue,
-            Color::White => Colour::White,
-            Color::Yellow => Colour::Yellow,
-            Color::Invalid => panic!("The Invalid color cannot be converted into a shell-displayeable color.")
+            Color::Orange => 'O',
+            Color::Green => 'G',
+            Color::Red => 'R',
+            Color::Blue => 'B',
+            Color::White => 'W',
+            Color::Yellow => 'Y',
+            Color::Invalid => '?'
         }
     }
 }
 
+//-----------------------------------------------------------------------------
+// Cube
+
 impl Face
 {
     /// turns a face into a single letter for display purposes
@@ -41,59 +131,151 @@ impl Face
     }
 }
 
-impl Cube
+impl<const SIDE: usize, const NB_SQUARES: usize> CubeOfSize<SIDE, NB_SQUARES>
 {
-    /// displays the cube in the shell
+    /// displays the cube in the shell, using the western color scheme
+    /// the letter of the face is displayed on the center square (if the cube has one)
     pub fn display(&self)
     {
-        // displays the square at a given 2D coordinate
-        let display_square = |face: Face, x: usize, y: usize| {
-            let color = self.get(face, x, y).to_shell_color();
-            let text = if (x == 1) && (y == 1)
+        self.display_with(&ColorScheme::western())
+    }
+
+    /// displays the cube in the shell, using the given color scheme
+    pub fn display_with(&self, scheme: &ColorScheme)
+    {
+        self.write_with(&mut stdout().lock(), scheme, DisplayMode::Ansi)
+            .expect("display: unable to write to the standard output")
+    }
+
+    /// writes the cube, as displayed by `display`, into the given writer
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()>
+    {
+        self.write_with(writer, &ColorScheme::western(), DisplayMode::Ansi)
+    }
+
+    /// writes the cube into the given writer, using the given color scheme and display mode
+    /// NOTE: the color scheme is ignored in ASCII mode
+    pub fn write_with<W: Write>(&self,
+                                writer: &mut W,
+                                scheme: &ColorScheme,
+                                mode: DisplayMode)
+                                -> io::Result<()>
+    {
+        let center = SIDE / 2;
+        let has_center = SIDE % 2 == 1;
+        let padding = "  ".repeat(SIDE);
+        // text of the square at a given 2D coordinate
+        let square_text = |face: Face, x: usize, y: usize| {
+            let color = self.get(face, x, y);
+            match mode
             {
-                format!("{} ", face.to_single_letter_string())
+                DisplayMode::Ascii => format!("{} ", color.to_letter()),
+                DisplayMode::Ansi =>
+                {
+                    let text = if has_center && (x == center) && (y == center)
+                    {
+                        format!("{} ", face.to_single_letter_string())
+                    }
+                    else
+                    {
+                        "  ".to_string()
+                    };
+                    let shell_color = scheme.shell_color(color);
+                    Style::new().on(shell_color).fg(Colour::Black).bold().paint(text).to_string()
+                }
             }
-            else
-            {
-                "  ".to_string()
-            };
-            let colored_text = Style::new().on(color).fg(Colour::Black).bold().paint(text);
-            print!("{}", colored_text);
         };
 
         // displays top square
-        for y in (0..NB_SQUARES_SIDE).rev()
+        for y in (0..SIDE).rev()
         {
-            print!("      "); // empty line
-            for x in 0..NB_SQUARES_SIDE
+            write!(writer, "{}", padding)?; // empty line
+            for x in 0..SIDE
             {
-                display_square(Face::Up, x, y);
+                write!(writer, "{}", square_text(Face::Up, x, y))?;
             }
-            println!();
+            writeln!(writer)?;
         }
 
         // displays middle squares
-        for y in (0..NB_SQUARES_SIDE).rev()
+        for y in (0..SIDE).rev()
         {
             for face in [Face::Left, Face::Front, Face::Right, Face::Back]
             {
-                for x in 0..NB_SQUARES_SIDE
+                for x in 0..SIDE
                 {
-                    display_square(face, x, y);
+                    write!(writer, "{}", square_text(face, x, y))?;
                 }
             }
-            println!();
+            writeln!(writer)?;
         }
 
         // displays bottom square
-        for y in (0..NB_SQUARES_SIDE).rev()
+        for y in (0..SIDE).rev()
         {
-            print!("      "); // empty line
-            for x in 0..NB_SQUARES_SIDE
+            write!(writer, "{}", padding)?; // empty line
+            for x in 0..SIDE
             {
-                display_square(Face::Down, x, y);
+                write!(writer, "{}", square_text(Face::Down, x, y))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// exports the cube as an SVG image of its net (same layout as `display`), using the western color scheme
+    pub fn to_svg(&self) -> String
+    {
+        self.to_svg_with(&ColorScheme::western())
+    }
+
+    /// exports the cube as an SVG image of its net (same layout as `display`), using the given color scheme
+    /// the letter of the face is displayed on the center square (if the cube has one)
+    pub fn to_svg_with(&self, scheme: &ColorScheme) -> String
+    {
+        // size of a square in pixels
+        const SQUARE_SIZE: usize = 30;
+        let center = SIDE / 2;
+        let has_center = SIDE % 2 == 1;
+        let width = 4 * SIDE * SQUARE_SIZE;
+        let height = 3 * SIDE * SQUARE_SIZE;
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" \
+                               width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+                              width, height, width, height);
+        // position of each face in the net, in number of faces
+        let faces = [(Face::Up, 1, 0),
+                     (Face::Left, 0, 1),
+                     (Face::Front, 1, 1),
+                     (Face::Right, 2, 1),
+                     (Face::Back, 3, 1),
+                     (Face::Down, 1, 2)];
+        for (face, column, row) in faces
+        {
+            for y in 0..SIDE
+            {
+                for x in 0..SIDE
+                {
+                    // the y axis goes up on the cube but down in the image
+                    let left = (column * SIDE + x) * SQUARE_SIZE;
+                    let top = (row * SIDE + SIDE - 1 - y) * SQUARE_SIZE;
+                    let (r, g, b) = scheme.rgb(self.get(face, x, y));
+                    svg += &format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+                                     fill=\"#{:02x}{:02x}{:02x}\" stroke=\"black\"/>\n",
+                                    left, top, SQUARE_SIZE, SQUARE_SIZE, r, g, b);
+                    if has_center && (x == center) && (y == center)
+                    {
+                        let middle = SQUARE_SIZE / 2;
+                        svg += &format!("  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" \
+                                         dominant-baseline=\"central\" font-family=\"sans-serif\" \
+                                         font-weight=\"bold\">{}</text>\n",
+                                        left + middle,
+                                        top + middle,
+                                        face.to_single_letter_string());
+                    }
+                }
             }
-            println!();
         }
+        svg += "</svg>\n";
+        svg
     }
 }
@@ -0,0 +1,240 @@
+//! Conversion from a string of facelets (one letter per square) into a cube
+//! and to and from the URFDLB order used by Kociemba's solver and most cube scanners
+use super::Cube;
+use super::color::{Color, NB_COLORS};
+use super::coordinates::{Coordinate2D, Face};
+use super::sizes::{NB_FACES, NB_SQUARES_CUBE, NB_SQUARES_FACE, NB_SQUARES_SIDE};
+
+//-----------------------------------------------------------------------------
+// Errors
+
+/// describes why a string could not be turned into a cube
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FaceletError
+{
+    /// the string does not contain exactly NB_SQUARES_CUBE letters
+    InvalidLength(usize),
+    /// the letter at the given index is neither a face letter nor a color initial
+    UnknownCharacter
+    {
+        index: usize,
+        character: char
+    },
+    /// a color does not appear exactly NB_SQUARES_FACE times
+    WrongColorCount
+    {
+        color: Color,
+        count: usize
+    }
+}
+
+impl std::fmt::Display for FaceletError
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self
+        {
+            FaceletError::InvalidLength(length) =>
+            {
+                write!(formater, "expected {} facelets but got {}", NB_SQUARES_CUBE, length)
+            }
+            FaceletError::UnknownCharacter { index, character } =>
+            {
+                write!(formater, "unknown facelet '{}' at index {}", character, index)
+            }
+            FaceletError::WrongColorCount { color, count } =>
+            {
+                write!(formater, "color {:?} appears {} times instead of {}", color, count, NB_SQUARES_FACE)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+//-----------------------------------------------------------------------------
+// Parsing
+
+impl Color
+{
+    /// turns a letter into a color
+    /// accepts both face letters (U R F D L B, using the western color scheme) and color initials (W R G Y O B)
+    pub fn from_letter(letter: char) -> Option<Color>
+    {
+        match letter
+        {
+            'L' | 'O' => Some(Color::Orange),
+            'F' | 'G' => Some(Color::Green),
+            'R' => Some(Color::Red),
+            'B' => Some(Color::Blue),
+            'U' | 'W' => Some(Color::White),
+            'D' | 'Y' => Some(Color::Yellow),
+            _ => None
+        }
+    }
+}
+
+impl Face
+{
+    /// turns a face letter (U R F D L B) into a face
+    pub fn from_letter(letter: char) -> Option<Face>
+    {
+        match letter
+        {
+            'L' => Some(Face::Left),
+            'F' => Some(Face::Front),
+            'R' => Some(Face::Right),
+            'B' => Some(Face::Back),
+            'U' => Some(Face::Up),
+            'D' => Some(Face::Down),
+            _ => None
+        }
+    }
+}
+
+impl Cube
+{
+    /// builds a cube from a string with one letter per square
+    /// the squares are given in the internal order of the cube (faces in [Left, Front, Right, Back, Up, Down] order)
+    /// whitespace is ignored so that faces can be separated for readability
+    pub fn from_facelets(s: &str) -> Result<Cube, FaceletError>
+    {
+        let letters: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if letters.len() != NB_SQUARES_CUBE
+        {
+            return Err(FaceletError::InvalidLength(letters.len()));
+        }
+
+        // converts the letters into colors
+        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+        for (index, (character, square)) in letters.into_iter().zip(squares.iter_mut()).enumerate()
+        {
+            *square =
+                Color::from_letter(character).ok_or(FaceletError::UnknownCharacter { index, character })?;
+        }
+
+        Cube::from_squares(squares)
+    }
+
+    /// builds a cube from its squares, checking that all colors appear the proper number of times
+    fn from_squares(squares: [Color; NB_SQUARES_CUBE]) -> Result<Cube, FaceletError>
+    {
+        let mut color_counts = [0; NB_COLORS];
+        for color in squares
+        {
+            color_counts[color as usize] += 1;
+        }
+
+        for color in Color::ALL
+        {
+            let count = color_counts[color as usize];
+            if count != NB_SQUARES_FACE
+            {
+                return Err(FaceletError::WrongColorCount { color, count });
+            }
+        }
+
+        Ok(Cube { squares })
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Kociemba order
+
+/// order in which the faces appear in a Kociemba string
+const KOCIEMBA_FACES: [Face; NB_FACES] =
+    [Face::Up, Face::Right, Face::Front, Face::Down, Face::Left, Face::Back];
+
+/// returns the 2D coordinates (x, y) of the square at the given index of a face in a Kociemba string
+/// the squares of a face are read row by row, from the top left corner,
+/// with the same point of view on the face as `Coordinate2D`
+fn kociemba_square_coordinates(index: usize) -> (usize, usize)
+{
+    let row = index / NB_SQUARES_SIDE;
+    let column = index % NB_SQUARES_SIDE;
+    (column, NB_SQUARES_SIDE - 1 - row)
+}
+
+impl Cube
+{
+    /// converts the cube into a Kociemba string: one face letter per square, faces in [Up, Right, Front, Down, Left, Back] order
+    /// each square is named after the face whose center has the same color
+    /// NOTE: `from_kociemba_string` reads the letters in the western color scheme,
+    /// a cube whose centers were moved (by slice moves) is thus recolored instead of round-tripping
+    pub fn to_kociemba_string(&self) -> String
+    {
+        // maps each color to the face that has it in its center
+        let center = NB_SQUARES_SIDE / 2;
+        let mut letters = vec![String::new(); NB_COLORS];
+        for face in KOCIEMBA_FACES
+        {
+            letters[self.get(face, center, center) as usize] = face.to_single_letter_string();
+        }
+
+        KOCIEMBA_FACES.iter()
+                      .flat_map(|&face| {
+                          (0..NB_SQUARES_FACE).map(move |index| (face, kociemba_square_coordinates(index)))
+                      })
+                      .map(|(face, (x, y))| letters[self.get(face, x, y) as usize].as_str())
+                      .collect()
+    }
+
+    /// builds a cube from a Kociemba string: one face letter per square, faces in [Up, Right, Front, Down, Left, Back] order
+    /// the letters are turned into colors using the western color scheme
+    /// whitespace is ignored so that faces can be separated for readability
+    pub fn from_kociemba_string(s: &str) -> Result<Cube, FaceletError>
+    {
+        let letters: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if letters.len() != NB_SQUARES_CUBE
+        {
+            return Err(FaceletError::InvalidLength(letters.len()));
+        }
+
+        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+        for (index, character) in letters.into_iter().enumerate()
+        {
+            // the letter is the face whose color, in the western color scheme, is the color of the square
+            let color_face =
+                Face::from_letter(character).ok_or(FaceletError::UnknownCharacter { index, character })?;
+            let face = KOCIEMBA_FACES[index / NB_SQUARES_FACE];
+            let (x, y) = kociemba_square_coordinates(index % NB_SQUARES_FACE);
+            let square = Coordinate2D::<NB_SQUARES_SIDE> { face, x, y }.to_1D().x;
+            squares[square] = Color::ALL[color_face as usize];
+        }
+
+        Cube::from_squares(squares)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+    use crate::cube::moves::MoveDescription;
+
+    /// the Kociemba string of a single `R` move, as produced by any Singmaster simulator
+    #[test]
+    fn kociemba_reference()
+    {
+        let cube = Cube::solved().apply_path(&Cube::parse_path("R").unwrap());
+        assert_eq!(cube.to_kociemba_string(), "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB");
+    }
+
+    /// converting a cube to a Kociemba string and back gives the same cube as long as its centers did not move
+    #[test]
+    fn kociemba_round_trip()
+    {
+        let face_moves: Vec<MoveDescription> =
+            MoveDescription::all().filter(|m| !m.kind.is_center_layer()).collect();
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..50
+        {
+            let scramble: Vec<MoveDescription> =
+                (0..20).map(|_| *face_moves.choose(&mut rng).unwrap()).collect();
+            let cube = Cube::solved().apply_path(&scramble);
+            let string = cube.to_kociemba_string();
+            assert!(Cube::from_kociemba_string(&string).unwrap() == cube, "{} did not round-trip", string);
+        }
+    }
+}
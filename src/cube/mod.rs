@@ -1,54 +1,98 @@
 use std::collections::BTreeSet;
+use std::sync::OnceLock;
 use enum_iterator::IntoEnumIterator;
-use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
 pub mod sizes;
 pub mod color;
 pub mod moves;
 pub mod coordinates;
+pub mod cubies;
+pub mod facelets;
+pub mod notation;
+pub mod path;
+pub mod pocket;
+pub mod symmetry;
+pub mod tracked;
+pub mod orientation;
+pub mod relative;
+pub mod patterns;
 mod display;
 pub use color::{Color, NB_COLORS};
-pub use sizes::{NB_FACES, NB_SQUARES_CUBE, NB_SQUARES_FACE, NB_SQUARES_SIDE};
-pub use moves::Move;
+pub use sizes::{NB_FACES, NB_SQUARES_CUBE, NB_SQUARES_FACE, NB_SQUARES_SIDE, NB_SQUARES_POCKET_CUBE};
+pub use moves::{Move, MoveOfSize, MoveKind, Metric, MoveTable, MoveTableOfSize};
+pub use moves::{CompiledPath, CompiledPathOfSize};
+pub use pocket::{PocketCube, PocketMove};
+pub use symmetry::{Symmetry, NB_SYMMETRIES};
+pub use tracked::{TrackedCube, TrackedCubeOfSize};
+pub use notation::{ParseMoveError, path_to_string};
+pub use facelets::FaceletError;
+pub use orientation::ReorientError;
+pub use cubies::InvalidCubeReason;
+pub use path::{invert_path, simplify_path};
+pub use path::{serialize_path, deserialize_path, PathFormatError, PATH_FORMAT_VERSION};
 pub use coordinates::{Face, Coordinate1D, Coordinate2D, RotationAxis};
+pub use display::{ColorScheme, Rgb, DisplayMode};
 
 //-----------------------------------------------------------------------------
 // Cube
 
-/// A Rubik's cube stored as a flat array of colors
+/// A cube with SIDE squares on each side stored as a flat array of NB_SQUARES colors
+/// NB_SQUARES is always `NB_FACES * SIDE * SIDE`, it is only a parameter because arrays cannot be sized by an expression
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Cube
+pub struct CubeOfSize<const SIDE: usize, const NB_SQUARES: usize>
 {
-    pub squares: [Color; NB_SQUARES_CUBE]
+    pub squares: [Color; NB_SQUARES]
 }
 
+/// A Rubik's cube (3x3)
+pub type Cube = CubeOfSize<NB_SQUARES_SIDE, NB_SQUARES_CUBE>;
+
 /// type used as a unique identifier for cubes
-type CubeIdentifier = u128;
+pub type CubeIdentifier = u128;
 
-impl Cube
+impl<const SIDE: usize, const NB_SQUARES: usize> CubeOfSize<SIDE, NB_SQUARES>
 {
-    /// produces a new, solved, Rubik's cube
+    /// fails at compile time if the number of squares does not match the side of the cube
+    pub(crate) const CHECK_SIZE: () =
+        assert!(NB_SQUARES == NB_FACES * SIDE * SIDE, "NB_SQUARES should be NB_FACES * SIDE * SIDE");
+
+    /// number of squares on a face of the cube
+    const NB_SQUARES_FACE: usize = SIDE * SIDE;
+
+    /// number of ways to orient a cube, and thus number of solved cubes (see `all_solved_cubes`)
+    pub const NB_SOLVED_ORIENTATIONS: usize = 24;
+
+    /// produces a new, solved, cube
     /// we use the western color scheme as a reference for the colors
     /// https://www.speedsolving.com/wiki/index.php/Western_Color_Scheme
-    pub fn solved() -> Cube
+    pub fn solved() -> Self
     {
-        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+        let () = Self::CHECK_SIZE;
+        let mut squares = [Color::Invalid; NB_SQUARES];
         let mut shift = 0;
         for color in Color::ALL
         {
-            for square in squares.iter_mut().skip(shift).take(NB_SQUARES_FACE)
+            for square in squares.iter_mut().skip(shift).take(Self::NB_SQUARES_FACE)
             {
                 *square = color;
             }
-            shift += NB_SQUARES_FACE;
+            shift += Self::NB_SQUARES_FACE;
         }
-        Cube { squares }
+        CubeOfSize { squares }
     }
 
     /// returns a vector of all possible solved cube
     /// done by rotating a solved cube until all possibilities are reached
-    pub fn all_solved_cubes() -> Vec<Cube>
+    pub fn all_solved_cubes() -> Vec<Self>
     {
-        let mut cubes = vec![Cube::solved()];
+        Self::solved().all_orientations()
+    }
+
+    /// returns all the distinct cubes that can be obtained by rotating the whole cube (including the cube itself)
+    /// there are 24 orientations, fewer if some of them produce the same squares
+    pub fn all_orientations(&self) -> Vec<Self>
+    {
+        let mut cubes = vec![self.clone()];
         let mut result = BTreeSet::new();
 
         while !cubes.is_empty()
@@ -73,21 +117,23 @@ impl Cube
         result.into_iter().collect()
     }
 
+    /// returns true if both cubes are in the same position, up to a rotation of the whole cube
+    /// NOTE: `==` compares the squares and thus considers that a rotated cube is a different cube
+    /// comparing identifiers is not enough either as they do not rotate the faces themselves
+    pub fn is_same_pattern(&self, other: &Self) -> bool
+    {
+        self.all_orientations().contains(other)
+    }
+
     /// returns true if a rubik's cube is solved
     pub fn is_solved(&self) -> bool
     {
         // checks that all faces are solved, one after the other
         // no need to check the last face as it will be solved if all other faces are solved
-        for index_face in 0..(NB_FACES - 1)
+        for (_, face) in self.faces().take(NB_FACES - 1)
         {
-            // extracts the face that we are currently checking
-            let start_index = index_face * NB_SQUARES_FACE;
-            let end_index = start_index + NB_SQUARES_FACE;
-            let face = &self.squares[start_index..end_index];
-            // gets the color of the face, all elements should be of this color
-            let face_color = face[0];
-            // returns early if at least one element is not of the target color
-            if face.iter().skip(1).any(|color| *color != face_color)
+            // returns early if at least one element is not of the color of the face
+            if !Self::is_face_solved(face)
             {
                 return false;
             }
@@ -95,18 +141,86 @@ impl Cube
         true
     }
 
+    /// returns true if all the squares of the face are of the same color
+    fn is_face_solved(face: &[Color]) -> bool
+    {
+        // gets the color of the face, all elements should be of this color
+        let face_color = face[0];
+        face.iter().skip(1).all(|color| *color == face_color)
+    }
+
+    /// returns the faces whose squares are all of the same color
+    /// useful to display the progress of a resolution
+    pub fn solved_faces(&self) -> Vec<Face>
+    {
+        self.faces().filter(|(_, face)| Self::is_face_solved(face)).map(|(face, _)| face).collect()
+    }
+
+    /// returns the number of faces whose squares are all of the same color (`NB_FACES` for a solved cube)
+    pub fn nb_solved_faces(&self) -> usize
+    {
+        self.faces().filter(|(_, face)| Self::is_face_solved(face)).count()
+    }
+
+    /// returns the number of squares whose color differs between the two cubes (0 if they are identical)
+    /// useful to check which squares a move touched when debugging its implementation
+    pub fn nb_mismatched_stickers(&self, other: &Self) -> usize
+    {
+        self.squares.iter().zip(&other.squares).filter(|(color, other_color)| color != other_color).count()
+    }
+
     /// gets the color at the given 2D coordinates
     pub fn get(&self, face: Face, x: usize, y: usize) -> Color
     {
-        let index = Coordinate2D { face, x, y }.to_1D().x;
+        let index = Coordinate2D::<SIDE> { face, x, y }.to_1D().x;
         self.squares[index]
     }
 
+    /// returns the colors of the squares of the given face
+    /// the square at 2D coordinates (x, y) is at index `x * SIDE + y` (see `Coordinate2D`)
+    pub fn face(&self, face: Face) -> &[Color]
+    {
+        let start_index = (face as usize) * Self::NB_SQUARES_FACE;
+        let end_index = start_index + Self::NB_SQUARES_FACE;
+        &self.squares[start_index..end_index]
+    }
+
+    /// iterates on the faces of the cube, in the order of `Face`, with the colors of their squares
+    /// (see `face` for the order of the squares)
+    pub fn faces(&self) -> impl Iterator<Item = (Face, &[Color])>
+    {
+        Face::into_enum_iter().map(move |face| (face, self.face(face)))
+    }
+
+    /// returns the colors of the squares as a grid indexed by `[face][x][y]`
+    /// faces are in the order of `Face` and x/y follow the convention of `Coordinate2D`
+    pub fn to_facelet_grid(&self) -> [[[Color; SIDE]; SIDE]; NB_FACES]
+    {
+        let mut faces = Face::into_enum_iter();
+        std::array::from_fn(|_| {
+            let face = faces.next().expect("there should be NB_FACES faces");
+            std::array::from_fn(|x| std::array::from_fn(|y| self.get(face, x, y)))
+        })
+    }
+
+    /// iterates on all the squares of the cube as (face, x, y, color), see `Coordinate2D` for the convention
+    /// the 3D position of the matching piece is given by `Coordinate2D::to_1D().piece_position()`
+    pub fn facelets(&self) -> impl Iterator<Item = (Face, usize, usize, Color)> + '_
+    {
+        self.squares
+            .iter()
+            .enumerate()
+            .map(|(index, &color)| {
+                let Coordinate2D { face, x, y } = Coordinate1D::<SIDE> { x: index }.to_2D();
+                (face, x, y, color)
+            })
+    }
+
     /// creates a new, scrambled, cube
-    pub fn scrambled(nb_scramble: usize) -> Cube
+    pub fn scrambled(nb_scramble: usize) -> Self
     {
         // selects a solved cube at random
-        let solved_cubes = Cube::all_solved_cubes();
+        let solved_cubes = Self::all_solved_cubes();
         let mut rng = rand::thread_rng();
         let cube = solved_cubes.choose(&mut rng).unwrap();
         // scrambles the solved cube
@@ -114,59 +228,121 @@ impl Cube
     }
 
     /// scrambles the cube a given number of times to produce a new, random, cube
-    pub fn scramble(self, nb_scramble: usize) -> Cube
+    pub fn scramble(self, nb_scramble: usize) -> Self
     {
         let mut rng = rand::thread_rng();
+        self.scramble_with_rng(nb_scramble, &mut rng)
+    }
+
+    /// scrambles the cube a given number of times using a seeded random number generator
+    /// the same seed will always produce the same cube (for a given version of the `rand` crate)
+    /// which makes it possible to reproduce a run
+    pub fn scramble_seeded(self, nb_scramble: usize, seed: u64) -> Self
+    {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.scramble_with_rng(nb_scramble, &mut rng)
+    }
+
+    /// scrambles the cube a given number of times, never picking a move that would merge with the previous ones
+    /// we forbid the kinds of the last moves as long as they are parallel layers (which commute)
+    /// so that the scramble is not shallower than `nb_scramble`
+    pub fn scramble_clean(self, nb_scramble: usize) -> Self
+    {
+        let mut rng = rand::thread_rng();
+        let mut result = self;
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        // kinds of the last moves, all turning along the same axis
+        let mut commuting_kinds: Vec<MoveKind> = Vec::new();
+        for _i in 0..nb_scramble
+        {
+            // picks a move among the allowed ones
+            let allowed_moves: Vec<&MoveOfSize<SIDE, NB_SQUARES>> =
+                moves.iter().filter(|m| !commuting_kinds.contains(&m.description.kind)).collect();
+            let random_move = allowed_moves.choose(&mut rng).unwrap();
+            result = result.apply_move(random_move);
+            // a move along another axis does not commute with the previous moves
+            let kind = random_move.description.kind;
+            let axis = kind.axis();
+            if commuting_kinds.first().is_some_and(|previous| previous.axis() != axis)
+            {
+                commuting_kinds.clear();
+            }
+            commuting_kinds.push(kind);
+        }
+        result
+    }
+
+    /// scrambles the cube a given number of times using the given random number generator
+    fn scramble_with_rng<R: Rng>(self, nb_scramble: usize, rng: &mut R) -> Self
+    {
         let mut result = self;
-        let moves = Move::all_moves();
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
         for _i in 0..nb_scramble
         {
-            let random_move = moves.choose(&mut rng).unwrap();
+            let random_move = moves.choose(rng).unwrap();
             result = result.apply_move(random_move);
         }
         result
     }
+}
+
+/// number of squares that are moved to another face by a single move of a Rubik's cube
+/// a face turn also moves the squares of the turned face, but they stay on the same face
+/// as the squares of a face of a solved cube share the same color, they cannot be put in place by the move
+const NB_SQUARES_CHANGING_FACE: usize = 12;
+
+impl Cube
+{
+    /// returns all possible solved cubes, computed once then shared by all calls
+    /// prefer it to `all_solved_cubes` when it is called often
+    pub fn solved_cubes() -> &'static [Cube]
+    {
+        static SOLVED_CUBES: OnceLock<Vec<Cube>> = OnceLock::new();
+        SOLVED_CUBES.get_or_init(Cube::all_solved_cubes)
+    }
+
+    /// returns a lower bound on the number of moves needed to solve the cube, from its misplaced squares
+    /// the cube is compared to the closest solved cube (in any orientation, as slice moves move the centers)
+    /// as a move puts at most `NB_SQUARES_CHANGING_FACE` squares on another face, it cannot fix more squares than that
+    /// NOTE: this is a very weak bound (never above 4), mostly useful as a baseline
+    pub fn distance_heuristic_sticker(&self) -> u8
+    {
+        let nb_mismatched_stickers =
+            Cube::solved_cubes().iter().map(|solved| self.nb_mismatched_stickers(solved)).min().unwrap_or(0);
+        nb_mismatched_stickers.div_ceil(NB_SQUARES_CHANGING_FACE) as u8
+    }
 
     /// converts the cube into a unique identifier
     /// we use the colors of the center squares to put the cube in standard orientation
     /// we do not encode the center square as it is always of the same color
-    /// we do not encode the last face as it is entirely deifned by the other faces
+    /// we do encode the last face: it cannot always be deduced from the other faces
+    /// (the middles of the last face can be permuted when their other color is the same)
+    /// 6^48 < 2^128 so all faces fit in the identifier
     pub fn to_identifier(&self) -> CubeIdentifier
     {
         // constants with the proper types
         let nb_colors_u128 = NB_COLORS as u128;
         let nb_squares_per_face = (NB_SQUARES_FACE - 1) as u32; // we ignore the center square
-        let index_face_ignored = (NB_FACES - 1) as u32; // the last face is not encoded
 
         // looping on all faces
         let mut result: u128 = 0;
-        for index_face in 0..NB_FACES
+        for (_, face) in self.faces()
         {
-            // extracts the face that we are currently checking
-            let start_index = index_face * NB_SQUARES_FACE;
-            let end_index = start_index + NB_SQUARES_FACE;
-            let face = &self.squares[start_index..end_index];
-
             // gets the index of the face using its center square
             let rotated_index_face = face[4] as u32;
-            // we do not encode the last face as it can be deduced from the other faces
-            if rotated_index_face == index_face_ignored
-            {
-                continue;
-            }
 
             // computes a code uniquely identifying the face
             let mut result_face = 0;
             // 4 squares before center square
-            for i in 0..4
+            for color in &face[0..4]
             {
-                let color_index = face[i] as usize;
+                let color_index = *color as usize;
                 result_face = result_face * NB_COLORS + color_index;
             }
             // 4 squares after center square
-            for i in 5..NB_SQUARES_FACE
+            for color in &face[5..NB_SQUARES_FACE]
             {
-                let color_index = face[i] as usize;
+                let color_index = *color as usize;
                 result_face = result_face * NB_COLORS + color_index;
             }
 
@@ -178,4 +354,126 @@ impl Cube
         }
         result
     }
+
+    /// returns the cube, rotated as a whole so that its centers are in the reference orientation (the one of `Cube::solved`)
+    /// all orientations of a cube share the same canonical cube which can be turned into an identifier and back
+    /// a cube whose centers cannot be put in the reference orientation (invalid centers) is returned unchanged
+    pub fn canonical(&self) -> Cube
+    {
+        let reference = Cube::solved();
+        let index_center = NB_SQUARES_FACE / 2;
+        let has_reference_centers = |cube: &Cube| {
+            (0..NB_FACES).map(|index_face| index_face * NB_SQUARES_FACE + index_center)
+                         .all(|index| cube.squares[index] == reference.squares[index])
+        };
+        self.all_orientations().into_iter().find(has_reference_centers).unwrap_or_else(|| self.clone())
+    }
+
+    /// rebuilds a cube from its identifier
+    /// the cube is produced in the reference orientation (the one of `Cube::solved`),
+    /// this is thus an exact inverse of `to_identifier` for cubes in that orientation
+    pub fn from_identifier(identifier: CubeIdentifier) -> Cube
+    {
+        // constants with the proper types
+        let face_size = (NB_COLORS as u128).pow((NB_SQUARES_FACE - 1) as u32); // we ignore the center square
+        let index_center = NB_SQUARES_FACE / 2;
+
+        // the faces are encoded in the order of the color of their center
+        // which is also the order of the faces in the reference orientation
+        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+        let mut identifier = identifier;
+        for index_face in 0..NB_FACES
+        {
+            let mut result_face = (identifier % face_size) as usize;
+            identifier /= face_size;
+            // the last square was encoded last, it is thus the first to be decoded
+            let start_index = index_face * NB_SQUARES_FACE;
+            for i in (0..NB_SQUARES_FACE).rev().filter(|i| *i != index_center)
+            {
+                squares[start_index + i] = Color::ALL[result_face % NB_COLORS];
+                result_face /= NB_COLORS;
+            }
+            squares[start_index + index_center] = Color::ALL[index_face];
+        }
+        Cube { squares }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// the same seed always produces the same cube, different seeds produce different cubes
+    #[test]
+    fn scramble_seeded_is_reproducible()
+    {
+        let cube = Cube::solved().scramble_seeded(20, 42);
+        assert!(cube == Cube::solved().scramble_seeded(20, 42));
+        assert!(cube != Cube::solved().scramble_seeded(20, 43));
+        assert_eq!(cube.is_valid(), Ok(()));
+    }
+
+    /// a canonical cube can be rebuilt from its identifier
+    #[test]
+    fn from_identifier_round_trip()
+    {
+        for solved in Cube::all_solved_cubes()
+        {
+            let canonical = solved.canonical();
+            assert!(canonical == Cube::solved());
+            assert!(Cube::from_identifier(canonical.to_identifier()) == canonical);
+        }
+        for seed in 0..50
+        {
+            let canonical = Cube::solved().scramble_seeded(20, seed).canonical();
+            assert!(Cube::from_identifier(canonical.to_identifier()) == canonical);
+        }
+    }
+
+    /// all solved cubes share a pattern, a rotated cube shares the pattern of the original cube
+    #[test]
+    fn is_same_pattern_ignores_orientation()
+    {
+        let solved_cubes = Cube::all_solved_cubes();
+        assert_eq!(solved_cubes.len(), Cube::NB_SOLVED_ORIENTATIONS);
+        assert!(solved_cubes.iter().all(|cube| cube.is_same_pattern(&Cube::solved())));
+        let cube = Cube::solved().scramble_seeded(20, 0);
+        assert!(cube.all_orientations().iter().all(|rotated| rotated.is_same_pattern(&cube)));
+        assert!(!cube.is_same_pattern(&Cube::solved().scramble_seeded(20, 1)));
+    }
+
+    /// the canonical cube of a canonical cube is itself and all orientations of a cube share their canonical cube
+    #[test]
+    fn canonical_is_idempotent()
+    {
+        for seed in 0..20
+        {
+            let cube = Cube::solved().scramble_seeded(20, seed);
+            let canonical = cube.canonical();
+            assert!(canonical.canonical() == canonical);
+            assert!(cube.all_orientations().iter().all(|rotated| rotated.canonical() == canonical));
+        }
+    }
+
+    /// the cached solved cubes are the ones computed from scratch
+    #[test]
+    fn solved_cubes_are_cached()
+    {
+        assert!(Cube::solved_cubes() == Cube::all_solved_cubes().as_slice());
+        assert!(std::ptr::eq(Cube::solved_cubes(), Cube::solved_cubes()));
+    }
+
+    /// all faces of a solved cube are solved, a single move leaves only the two faces parallel to the turned layer
+    #[test]
+    fn nb_solved_faces_after_a_move()
+    {
+        assert_eq!(Cube::solved().nb_solved_faces(), NB_FACES);
+        assert_eq!(Cube::solved().solved_faces().len(), NB_FACES);
+        for m in Move::all_moves()
+        {
+            let cube = Cube::solved().apply_move(&m);
+            assert_eq!(cube.nb_solved_faces(), 2);
+        }
+    }
 }
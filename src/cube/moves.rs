@@ -3,8 +3,8 @@
 //! See this website for the classical notations:
 //! http://www.rubiksplace.com/move-notations/
 use enum_iterator::IntoEnumIterator;
-use super::Cube;
-use super::sizes::NB_SQUARES_CUBE;
+use super::CubeOfSize;
+use super::sizes::{NB_SQUARES_CUBE, NB_SQUARES_SIDE};
 use super::coordinates::{Coordinate1D, RotationAxis};
 use super::color::Color;
 
@@ -41,6 +41,26 @@ impl MoveKind
     {
         matches!(self, MoveKind::Equator | MoveKind::Middle | MoveKind::Side)
     }
+
+    /// axis along which the layer turns
+    /// layers turning along the same axis are parallel and thus commute
+    pub fn axis(&self) -> RotationAxis
+    {
+        match self
+        {
+            MoveKind::Front | MoveKind::Side | MoveKind::Back => RotationAxis::FrontBack,
+            MoveKind::Right | MoveKind::Middle | MoveKind::Left => RotationAxis::LeftRight,
+            MoveKind::Down | MoveKind::Equator | MoveKind::Up => RotationAxis::DownUp
+        }
+    }
+
+    /// returns true if both kinds are faces on opposite sides of the cube
+    /// (center layers have no opposite)
+    pub fn is_opposite(&self, other: &MoveKind) -> bool
+    {
+        let are_faces = !self.is_center_layer() && !other.is_center_layer();
+        are_faces && (self != other) && (self.axis() == other.axis())
+    }
 }
 
 impl std::fmt::Display for MoveKind
@@ -88,6 +108,30 @@ impl Amplitude
             Amplitude::Counterclockwise => 3
         }
     }
+
+    /// returns the amplitude obtained by applying the given number of 90° clockwise rotations
+    /// returns None if the rotations cancel out
+    pub fn from_nb_rotations(nb_rotations: usize) -> Option<Amplitude>
+    {
+        match nb_rotations % 4
+        {
+            1 => Some(Amplitude::Clockwise),
+            2 => Some(Amplitude::Fullturn),
+            3 => Some(Amplitude::Counterclockwise),
+            _ => None
+        }
+    }
+
+    /// returns the amplitude that cancels this one
+    pub fn inverse(self) -> Amplitude
+    {
+        match self
+        {
+            Amplitude::Clockwise => Amplitude::Counterclockwise,
+            Amplitude::Fullturn => Amplitude::Fullturn,
+            Amplitude::Counterclockwise => Amplitude::Clockwise
+        }
+    }
 }
 
 /// describes all possible moves
@@ -98,6 +142,48 @@ pub struct MoveDescription
     pub amplitude: Amplitude
 }
 
+impl MoveDescription
+{
+    /// iterates over the descriptions of all possible moves (in the half turn metric), from either end
+    /// the moves are ordered by kind then by amplitude, which is the order used by `Move::all_moves`
+    /// NOTE: unlike `Move::all_moves`, this does not compute the permutations applied by the moves
+    pub fn all() -> impl DoubleEndedIterator<Item = MoveDescription> + ExactSizeIterator
+    {
+        let kinds: Vec<MoveKind> = MoveKind::into_enum_iter().collect();
+        let amplitudes: Vec<Amplitude> = Amplitude::into_enum_iter().collect();
+        (0..(kinds.len() * amplitudes.len())).map(move |index| {
+                                                 let kind = kinds[index / amplitudes.len()];
+                                                 let amplitude = amplitudes[index % amplitudes.len()];
+                                                 MoveDescription { kind, amplitude }
+                                             })
+    }
+
+    /// returns the move that cancels this one
+    pub fn inverse(self) -> MoveDescription
+    {
+        MoveDescription { kind: self.kind, amplitude: self.amplitude.inverse() }
+    }
+}
+
+/// the way the length of a path is measured
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Metric
+{
+    /// half turn metric, 180° turns count as a single move
+    HalfTurn,
+    /// quarter turn metric, 180° turns count as two moves (they are thus not moves in themselves)
+    QuarterTurn
+}
+
+impl Metric
+{
+    /// returns true if the move counts as a single move in this metric
+    pub fn is_single_move(self, description: &MoveDescription) -> bool
+    {
+        (self == Metric::HalfTurn) || (description.amplitude != Amplitude::Fullturn)
+    }
+}
+
 impl std::fmt::Debug for MoveDescription
 {
     /// displays a move description in standard format
@@ -113,42 +199,60 @@ impl std::fmt::Debug for MoveDescription
 /// a way to twist the cube
 /// note that this particular representation takes some memory,
 /// MoveDescription are more suited if one just want to recall a move
-pub struct Move
+/// SIDE and NB_SQUARES are the dimensions of the cube the move applies to (see `CubeOfSize`)
+pub struct MoveOfSize<const SIDE: usize, const NB_SQUARES: usize>
 {
     /// unique identifier for the move
     pub description: MoveDescription,
     /// a permutation table, precomputed to speed up move computation
-    pub permutation: [usize; NB_SQUARES_CUBE]
+    pub permutation: [usize; NB_SQUARES]
 }
 
-impl Move
+/// a way to twist a Rubik's cube (3x3)
+pub type Move = MoveOfSize<NB_SQUARES_SIDE, NB_SQUARES_CUBE>;
+
+impl<const SIDE: usize, const NB_SQUARES: usize> MoveOfSize<SIDE, NB_SQUARES>
 {
     /// takes a move description and compiles it down to a permutation table
     /// NOTE: this step is too expensive to be run whenever a move needs to be applied, instead it is meant as a preparation step
-    fn new(kind: MoveKind, amplitude: Amplitude) -> Move
+    fn new(kind: MoveKind, amplitude: Amplitude) -> Self
     {
+        let () = CubeOfSize::<SIDE, NB_SQUARES>::CHECK_SIZE;
         // builds the description of the move
         let description = MoveDescription { kind, amplitude };
         // generate the associated permutation table
-        let mut permutation: [usize; NB_SQUARES_CUBE] = [0; NB_SQUARES_CUBE];
+        let mut permutation: [usize; NB_SQUARES] = [0; NB_SQUARES];
         for (index, result) in permutation.iter_mut().enumerate()
         {
             // new index obtained once we apply the move
-            let new_index = Coordinate1D::new(index).apply_move(&description).x;
+            let new_index = Coordinate1D::<SIDE> { x: index }.apply_move(&description).x;
             *result = new_index;
         }
-        Move { description, permutation }
+        MoveOfSize { description, permutation }
     }
 
-    /// returns a vector containing all possible moves
-    pub fn all_moves() -> Vec<Move>
+    /// returns a vector containing all possible moves (in the half turn metric)
+    /// NOTE: cubes with an even side have no center layer and thus no center layer moves
+    /// the inner layers of cubes larger than 3x3 cannot be moved (only their faces can)
+    pub fn all_moves() -> Vec<Self>
     {
-        MoveKind::into_enum_iter().flat_map(|kind| {
-                                      Amplitude::into_enum_iter().map(move |amplitude| {
-                                                                     Move::new(kind, amplitude)
-                                                                 })
-                                  })
-                                  .collect()
+        Self::all_moves_with_metric(Metric::HalfTurn)
+    }
+
+    /// returns a vector containing all possible moves in the quarter turn metric (no 180° turns)
+    pub fn all_moves_qtm() -> Vec<Self>
+    {
+        Self::all_moves_with_metric(Metric::QuarterTurn)
+    }
+
+    /// returns a vector containing all the moves that count as a single move in the given metric
+    pub fn all_moves_with_metric(metric: Metric) -> Vec<Self>
+    {
+        let has_center_layer = SIDE % 2 == 1;
+        MoveDescription::all().filter(|description| has_center_layer || !description.kind.is_center_layer())
+                               .filter(|description| metric.is_single_move(description))
+                               .map(|description| MoveOfSize::new(description.kind, description.amplitude))
+                               .collect()
     }
 
     /// returns the new coordinate obtained after applying the move
@@ -159,45 +263,209 @@ impl Move
     }
 }
 
+//-----------------------------------------------------------------------------
+// Move table
+
+/// all the moves of a cube, indexed by their description
+/// building it once lets us convert descriptions into moves in constant time
+pub struct MoveTableOfSize<const SIDE: usize, const NB_SQUARES: usize>
+{
+    /// index of each move in `moves`, None if the move is not available (center layers of even cubes)
+    indexes: [[Option<usize>; Amplitude::VARIANT_COUNT]; MoveKind::VARIANT_COUNT],
+    moves: Vec<MoveOfSize<SIDE, NB_SQUARES>>
+}
+
+/// move table for a Rubik's cube (3x3)
+pub type MoveTable = MoveTableOfSize<NB_SQUARES_SIDE, NB_SQUARES_CUBE>;
+
+impl<const SIDE: usize, const NB_SQUARES: usize> MoveTableOfSize<SIDE, NB_SQUARES>
+{
+    /// builds a table containing all possible moves
+    pub fn new() -> Self
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let mut indexes = [[None; Amplitude::VARIANT_COUNT]; MoveKind::VARIANT_COUNT];
+        for (index, m) in moves.iter().enumerate()
+        {
+            indexes[m.description.kind as usize][m.description.amplitude as usize] = Some(index);
+        }
+        MoveTableOfSize { indexes, moves }
+    }
+
+    /// returns the move with the given description
+    /// panics if the move is not available for this size of cube
+    pub fn get(&self, description: &MoveDescription) -> &MoveOfSize<SIDE, NB_SQUARES>
+    {
+        let index = self.indexes[description.kind as usize][description.amplitude as usize]
+                        .expect("MoveTable: this move is not available for this size of cube");
+        &self.moves[index]
+    }
+}
+
+impl<const SIDE: usize, const NB_SQUARES: usize> Default for MoveTableOfSize<SIDE, NB_SQUARES>
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Compiled path
+
+/// a path compiled down to a single permutation table
+/// applying it costs as much as applying a single move, whatever the length of the path
+/// which is useful when the same path needs to be applied to a lot of cubes
+pub struct CompiledPathOfSize<const SIDE: usize, const NB_SQUARES: usize>
+{
+    /// the moves that were composed
+    pub path: Vec<MoveDescription>,
+    /// a permutation table, equivalent to the permutations of the moves applied one after the other
+    pub permutation: [usize; NB_SQUARES]
+}
+
+/// a path compiled for a Rubik's cube (3x3)
+pub type CompiledPath = CompiledPathOfSize<NB_SQUARES_SIDE, NB_SQUARES_CUBE>;
+
+impl<const SIDE: usize, const NB_SQUARES: usize> CompiledPathOfSize<SIDE, NB_SQUARES>
+{
+    /// composes the permutation tables of the moves of the path
+    /// NOTE: this builds a new move table at each call, use `new_with` if you need to compile a lot of paths
+    pub fn new(path: &[MoveDescription]) -> Self
+    {
+        Self::new_with(&MoveTableOfSize::new(), path)
+    }
+
+    /// composes the permutation tables of the moves of the path, using a precomputed move table
+    pub fn new_with(table: &MoveTableOfSize<SIDE, NB_SQUARES>, path: &[MoveDescription]) -> Self
+    {
+        let mut permutation: [usize; NB_SQUARES] = [0; NB_SQUARES];
+        for (index, result) in permutation.iter_mut().enumerate()
+        {
+            // follows the square through all the moves
+            *result = path.iter().fold(index, |index, description| table.get(description).apply(index));
+        }
+        CompiledPathOfSize { path: path.to_vec(), permutation }
+    }
+
+    /// returns the new coordinate obtained after applying the full path
+    pub fn apply(&self, coordinate1D: usize) -> usize
+    {
+        self.permutation[coordinate1D]
+    }
+}
+
 //-----------------------------------------------------------------------------
 // Cube
 
-impl Cube
+impl<const SIDE: usize, const NB_SQUARES: usize> CubeOfSize<SIDE, NB_SQUARES>
 {
     /// takes a move and produces a new, twisted, cube by applying the move
-    pub fn apply_move(&self, m: &Move) -> Cube
+    pub fn apply_move(&self, m: &MoveOfSize<SIDE, NB_SQUARES>) -> Self
+    {
+        let mut result = CubeOfSize { squares: [Color::Invalid; NB_SQUARES] };
+        self.apply_move_into(m, &mut result);
+        result
+    }
+
+    /// applies a move and writes the twisted cube into `out`
+    /// the move is a permutation: every square of `out` is overwritten, whatever its previous content
+    pub fn apply_move_into(&self, m: &MoveOfSize<SIDE, NB_SQUARES>, out: &mut Self)
     {
-        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
         for (index, color) in self.squares.iter().cloned().enumerate()
         {
-            squares[m.apply(index)] = color;
+            out.squares[m.apply(index)] = color;
         }
-        Cube { squares }
+    }
+
+    /// applies a move to the cube itself
+    /// as the move reads all squares before writing them, `buffer` is used as temporary storage:
+    /// its previous content is ignored and it holds the cube before the move once the function returns
+    pub fn apply_move_mut(&mut self, m: &MoveOfSize<SIDE, NB_SQUARES>, buffer: &mut Self)
+    {
+        self.apply_move_into(m, buffer);
+        std::mem::swap(self, buffer);
     }
 
     /// applies a full path to a cube
-    /// NOTE: this operation is not designed with efficiency in mind
-    pub fn apply_path(&self, path: &[MoveDescription]) -> Cube
+    /// NOTE: this builds a new move table at each call,
+    /// use `apply_path_with` if you need to apply a lot of paths
+    pub fn apply_path(&self, path: &[MoveDescription]) -> Self
+    {
+        self.apply_path_with(&MoveTableOfSize::new(), path)
+    }
+
+    /// applies a full path to a cube, using a precomputed move table
+    pub fn apply_path_with(&self, table: &MoveTableOfSize<SIDE, NB_SQUARES>, path: &[MoveDescription]) -> Self
     {
-        let moves = Move::all_moves();
         let mut cube = self.clone();
+        let mut buffer = self.clone();
         for moveDescription in path
         {
-            let m = moves.iter().find(|m| m.description == *moveDescription).unwrap();
-            cube = cube.apply_move(m);
+            cube.apply_move_mut(table.get(moveDescription), &mut buffer);
         }
         cube
     }
 
+    /// applies a full path to a cube, returning all the cubes visited along the way
+    /// the result starts with the cube itself and ends with the cube obtained once the full path is applied
+    pub fn trace_path(&self, path: &[MoveDescription]) -> Vec<Self>
+    {
+        let table = MoveTableOfSize::new();
+        let mut cubes = Vec::with_capacity(path.len() + 1);
+        cubes.push(self.clone());
+        for moveDescription in path
+        {
+            let cube = cubes.last().unwrap().apply_move(table.get(moveDescription));
+            cubes.push(cube);
+        }
+        cubes
+    }
+
+    /// applies a compiled path to a cube in a single pass over its squares
+    pub fn apply_compiled_path(&self, path: &CompiledPathOfSize<SIDE, NB_SQUARES>) -> Self
+    {
+        let mut squares = [Color::Invalid; NB_SQUARES];
+        for (index, color) in self.squares.iter().cloned().enumerate()
+        {
+            squares[path.apply(index)] = color;
+        }
+        CubeOfSize { squares }
+    }
+
     /// rotates the cube along the given axis
-    pub fn rotate(&self, axis: RotationAxis) -> Cube
+    pub fn rotate(&self, axis: RotationAxis) -> Self
     {
-        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+        let mut squares = [Color::Invalid; NB_SQUARES];
         for (index, color) in self.squares.iter().cloned().enumerate()
         {
-            let new_index = Coordinate1D::new(index).rotate(axis).x;
+            let new_index = Coordinate1D::<SIDE> { x: index }.rotate(axis).x;
             squares[new_index] = color;
         }
-        Cube { squares }
+        CubeOfSize { squares }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+    use crate::cube::{Cube, CompiledPath};
+
+    /// applying a compiled path gives the same cube as applying its moves one after the other
+    #[test]
+    fn compiled_path_matches_apply_path()
+    {
+        let table = MoveTable::new();
+        let mut rng = StdRng::seed_from_u64(0);
+        for length in 0..30
+        {
+            let path: Vec<MoveDescription> =
+                (0..length).map(|_| MoveDescription::all().choose(&mut rng).unwrap()).collect();
+            let compiled = CompiledPath::new_with(&table, &path);
+            let start = Cube::solved().scramble_seeded(20, length as u64);
+            assert!(start.apply_compiled_path(&compiled) == start.apply_path(&path));
+        }
     }
 }
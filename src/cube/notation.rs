@@ -0,0 +1,224 @@
+//! Conversion between moves and the standard (Singmaster) notation
+//!
+//! In this crate, all layers turning along the same axis turn in the same direction
+//! (the one of the Right, Down and Back faces) whereas, in Singmaster notation,
+//! each face turns clockwise when seen from its side and the middle layers follow the Left, Down and Front faces.
+//! The L, U, F, M and S moves thus have their amplitude inverted when converted to and from the notation.
+//!
+//! See this website for the classical notations:
+//! http://www.rubiksplace.com/move-notations/
+use std::str::FromStr;
+use super::Cube;
+use super::moves::{MoveKind, Amplitude, MoveDescription};
+
+//-----------------------------------------------------------------------------
+// Errors
+
+/// describes why a string could not be turned into a move
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoveError
+{
+    /// there was no move to parse
+    Empty,
+    /// the letter does not correspond to any slice of the cube
+    UnknownKind(char),
+    /// the suffix is neither nothing, `2` nor `'`
+    InvalidAmplitude(String),
+    /// a move, inside a path, could not be parsed
+    InvalidToken
+    {
+        /// position of the move in the path (starting from 0)
+        index: usize,
+        /// the text of the move
+        token: String,
+        /// why it could not be parsed
+        error: Box<ParseMoveError>
+    }
+}
+
+impl std::fmt::Display for ParseMoveError
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self
+        {
+            ParseMoveError::Empty => write!(formater, "cannot parse a move from an empty string"),
+            ParseMoveError::UnknownKind(c) =>
+            {
+                write!(formater, "unknown move letter '{}' (expected one of F B R L U D M E S)", c)
+            }
+            ParseMoveError::InvalidAmplitude(suffix) =>
+            {
+                write!(formater, "invalid amplitude '{}' (expected nothing, '2' or ''')", suffix)
+            }
+            ParseMoveError::InvalidToken { index, token, error } =>
+            {
+                write!(formater, "unable to parse move {} ('{}'): {}", index, token, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+//-----------------------------------------------------------------------------
+// Parsing
+
+/// converts the amplitude of a move between this crate and the Singmaster notation
+/// (the conversion is its own inverse)
+fn notation_amplitude(kind: MoveKind, amplitude: Amplitude) -> Amplitude
+{
+    match kind
+    {
+        MoveKind::Left | MoveKind::Up | MoveKind::Front | MoveKind::Middle | MoveKind::Side =>
+        {
+            amplitude.inverse()
+        }
+        MoveKind::Right | MoveKind::Down | MoveKind::Back | MoveKind::Equator => amplitude
+    }
+}
+
+impl MoveKind
+{
+    /// turns a single letter, as produced by `Display`, into a MoveKind
+    pub fn from_letter(letter: char) -> Result<MoveKind, ParseMoveError>
+    {
+        match letter
+        {
+            'F' => Ok(MoveKind::Front),
+            'B' => Ok(MoveKind::Back),
+            'R' => Ok(MoveKind::Right),
+            'L' => Ok(MoveKind::Left),
+            'U' => Ok(MoveKind::Up),
+            'D' => Ok(MoveKind::Down),
+            'M' => Ok(MoveKind::Middle),
+            'E' => Ok(MoveKind::Equator),
+            'S' => Ok(MoveKind::Side),
+            _ => Err(ParseMoveError::UnknownKind(letter))
+        }
+    }
+}
+
+impl Amplitude
+{
+    /// turns a notation suffix into an amplitude
+    /// no suffix is a clockwise turn, `2` a full turn and `'` a counter-clockwise turn
+    pub fn from_suffix(suffix: &str) -> Result<Amplitude, ParseMoveError>
+    {
+        match suffix
+        {
+            "" => Ok(Amplitude::Clockwise),
+            "2" => Ok(Amplitude::Fullturn),
+            "'" => Ok(Amplitude::Counterclockwise),
+            _ => Err(ParseMoveError::InvalidAmplitude(suffix.to_string()))
+        }
+    }
+}
+
+impl FromStr for MoveDescription
+{
+    type Err = ParseMoveError;
+
+    /// parses a move written in standard notation such as `R`, `U2` or `F'`
+    fn from_str(s: &str) -> Result<MoveDescription, ParseMoveError>
+    {
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or(ParseMoveError::Empty)?;
+        let kind = MoveKind::from_letter(letter)?;
+        let amplitude = notation_amplitude(kind, Amplitude::from_suffix(chars.as_str())?);
+        Ok(MoveDescription { kind, amplitude })
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Printing
+
+impl std::fmt::Display for Amplitude
+{
+    /// prints an amplitude as a notation suffix (nothing, `2` or `'`)
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        let suffix = match self
+        {
+            Amplitude::Clockwise => "",
+            Amplitude::Fullturn => "2",
+            Amplitude::Counterclockwise => "'"
+        };
+        write!(formater, "{}", suffix)
+    }
+}
+
+impl std::fmt::Display for MoveDescription
+{
+    /// prints a move in standard notation such as `R`, `U2` or `F'`
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        write!(formater, "{}{}", self.kind, notation_amplitude(self.kind, self.amplitude))
+    }
+}
+
+/// turns a path into a space-separated string in standard notation
+/// the output can be parsed back with `Cube::parse_path`
+pub fn path_to_string(path: &[MoveDescription]) -> String
+{
+    path.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+//-----------------------------------------------------------------------------
+// Path
+
+impl Cube
+{
+    /// parses a whitespace-separated sequence of moves such as `R U R' U' M2 E'`
+    /// the error reports the index of the first move that could not be parsed
+    pub fn parse_path(s: &str) -> Result<Vec<MoveDescription>, ParseMoveError>
+    {
+        s.split_whitespace()
+         .enumerate()
+         .map(|(index, token)| {
+             token.parse().map_err(|error| ParseMoveError::InvalidToken { index,
+                                                                          token: token.to_string(),
+                                                                          error: Box::new(error) })
+         })
+         .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::cube::moves::Move;
+
+    /// moves parsed from the notation match the Singmaster convention
+    #[test]
+    fn singmaster_reference()
+    {
+        let path = Cube::parse_path("R U R' U'").unwrap();
+        let cube = Cube::solved().apply_path(&path);
+        assert_eq!(cube.to_kociemba_string(), "UULUUFUUFRRUBRRURRFFDFFUFFFDDRDDDDDDBLLLLLLLLBRRBBBBBB");
+    }
+
+    /// printing then parsing a move gives back the same move
+    #[test]
+    fn display_round_trip()
+    {
+        for m in Move::all_moves()
+        {
+            let parsed: MoveDescription = m.description.to_string().parse().unwrap();
+            assert!(parsed == m.description, "{:?} was parsed back as {:?}", m.description, parsed);
+        }
+    }
+
+    /// malformed moves are reported instead of panicking
+    #[test]
+    fn parse_errors()
+    {
+        assert_eq!("".parse::<MoveDescription>().err(), Some(ParseMoveError::Empty));
+        assert_eq!("X".parse::<MoveDescription>().err(), Some(ParseMoveError::UnknownKind('X')));
+        let invalid_amplitude = ParseMoveError::InvalidAmplitude("3".to_string());
+        assert_eq!("R3".parse::<MoveDescription>().err(), Some(invalid_amplitude));
+        let error = Cube::parse_path("R U X").unwrap_err();
+        assert!(matches!(error, ParseMoveError::InvalidToken { index: 2, .. }));
+    }
+}
@@ -0,0 +1,97 @@
+//! Rotation of the whole cube into a given orientation
+use enum_iterator::IntoEnumIterator;
+use super::CubeOfSize;
+use super::coordinates::{Face, RotationAxis};
+
+//-----------------------------------------------------------------------------
+// Errors
+
+/// describes why a cube could not be put in the requested orientation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReorientError
+{
+    /// the same face was requested on the up and front positions
+    SameFace(Face),
+    /// the faces requested on the up and front positions are on opposite sides of the cube
+    OppositeFaces
+    {
+        up: Face,
+        front: Face
+    }
+}
+
+impl std::fmt::Display for ReorientError
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self
+        {
+            ReorientError::SameFace(face) =>
+            {
+                write!(formater, "the {:?} face cannot be both the up and front faces", face)
+            }
+            ReorientError::OppositeFaces { up, front } =>
+            {
+                write!(formater, "the {:?} and {:?} faces are opposite, they cannot be adjacent", up, front)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReorientError {}
+
+//-----------------------------------------------------------------------------
+// Reorientation
+
+impl<const SIDE: usize, const NB_SQUARES: usize> CubeOfSize<SIDE, NB_SQUARES>
+{
+    /// rotates the whole cube so that the face currently in the `up` position ends up in the Up position
+    /// and the face currently in the `front` position ends up in the Front position
+    /// fails if the faces are equal or opposite (as they could not be adjacent)
+    pub fn reorient(&self, up: Face, front: Face) -> Result<Self, ReorientError>
+    {
+        if up == front
+        {
+            return Err(ReorientError::SameFace(up));
+        }
+        if up.opposite() == front
+        {
+            return Err(ReorientError::OppositeFaces { up, front });
+        }
+
+        // follows the faces of a solved cube (which all have different colors) to find the rotations needed
+        let reference = Self::solved();
+        let up_color = reference.get(up, 0, 0);
+        let front_color = reference.get(front, 0, 0);
+        let is_target = |cube: &Self| {
+            (cube.get(Face::Up, 0, 0) == up_color) && (cube.get(Face::Front, 0, 0) == front_color)
+        };
+        // breadth first search over the sequences of rotations
+        let mut orientations = vec![(reference.clone(), Vec::new())];
+        let mut seen_orientations = vec![reference];
+        loop
+        {
+            let mut new_orientations = Vec::new();
+            for (cube, axes) in orientations
+            {
+                if is_target(&cube)
+                {
+                    let result = axes.iter().fold(self.clone(), |result: Self, axis| result.rotate(*axis));
+                    return Ok(result);
+                }
+                for axis in RotationAxis::into_enum_iter()
+                {
+                    let new_cube = cube.rotate(axis);
+                    if !seen_orientations.contains(&new_cube)
+                    {
+                        seen_orientations.push(new_cube.clone());
+                        let mut new_axes = axes.clone();
+                        new_axes.push(axis);
+                        new_orientations.push((new_cube, new_axes));
+                    }
+                }
+            }
+            orientations = new_orientations;
+        }
+    }
+}
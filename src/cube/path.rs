@@ -0,0 +1,229 @@
+//! Operations on paths (sequences of moves)
+use enum_iterator::IntoEnumIterator;
+use super::moves::{MoveKind, Amplitude, MoveDescription};
+use super::coordinates::RotationAxis;
+
+/// returns the path that undoes the given path
+/// (the moves are reversed and each move is inverted)
+pub fn invert_path(path: &[MoveDescription]) -> Vec<MoveDescription>
+{
+    path.iter().rev().map(|m| m.inverse()).collect()
+}
+
+//-----------------------------------------------------------------------------
+// Simplification
+
+/// consecutive moves that commute with one another (they all turn along the same axis)
+/// stored as a number of 90° clockwise rotations per kind, in order of first appearance
+struct MoveGroup
+{
+    axis: RotationAxis,
+    rotations: Vec<(MoveKind, usize)>
+}
+
+impl MoveGroup
+{
+    /// returns true if the move commutes with all moves of the group
+    fn accepts(&self, m: &MoveDescription) -> bool
+    {
+        m.kind.axis() == self.axis
+    }
+
+    /// adds a move to the group, merging it with previous moves of the same kind
+    fn push(&mut self, m: &MoveDescription)
+    {
+        match self.rotations.iter_mut().find(|(kind, _)| *kind == m.kind)
+        {
+            Some((_, nb_rotations)) => *nb_rotations = (*nb_rotations + m.amplitude.nb_rotations()) % 4,
+            None => self.rotations.push((m.kind, m.amplitude.nb_rotations()))
+        }
+        self.rotations.retain(|(_, nb_rotations)| *nb_rotations != 0);
+    }
+}
+
+/// returns a shorter path that has the same effect on a cube
+/// consecutive moves of the same kind are merged (`R R` becomes `R2`, `R R'` disappears)
+/// and moves separated only by turns of parallel layers, which commute, are merged
+/// (`R L R'` becomes `L` and `U E D U'` becomes `E D`)
+pub fn simplify_path(path: &[MoveDescription]) -> Vec<MoveDescription>
+{
+    let mut groups: Vec<MoveGroup> = Vec::new();
+    for m in path
+    {
+        match groups.last_mut()
+        {
+            Some(group) if group.accepts(m) =>
+            {
+                group.push(m);
+                // a group that cancels out lets its neighbours merge
+                if group.rotations.is_empty()
+                {
+                    groups.pop();
+                }
+            }
+            _ =>
+            {
+                let mut group = MoveGroup { axis: m.kind.axis(), rotations: Vec::new() };
+                group.push(m);
+                groups.push(group);
+            }
+        }
+    }
+
+    groups.iter()
+          .flat_map(|group| group.rotations.iter())
+          .map(|(kind, nb_rotations)| {
+              let amplitude = Amplitude::from_nb_rotations(*nb_rotations).unwrap();
+              MoveDescription { kind: *kind, amplitude }
+          })
+          .collect()
+}
+
+//-----------------------------------------------------------------------------
+// Serialization
+
+/// version of the binary format produced by `serialize_path`, stored in its first byte
+pub const PATH_FORMAT_VERSION: u8 = 1;
+
+/// describes why some bytes could not be turned into a path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathFormatError
+{
+    /// there was not even a header byte
+    Empty,
+    /// the header byte does not correspond to a known version of the format
+    UnknownVersion(u8),
+    /// a byte does not correspond to any move
+    InvalidMove
+    {
+        /// position of the move in the path (starting from 0)
+        index: usize,
+        byte: u8
+    }
+}
+
+impl std::fmt::Display for PathFormatError
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self
+        {
+            PathFormatError::Empty => write!(formater, "cannot deserialize a path from an empty buffer"),
+            PathFormatError::UnknownVersion(version) =>
+            {
+                write!(formater, "unknown path format version {} (expected {})", version, PATH_FORMAT_VERSION)
+            }
+            PathFormatError::InvalidMove { index, byte } =>
+            {
+                write!(formater, "byte {} of move {} does not correspond to any move", byte, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PathFormatError {}
+
+/// turns a path into a compact binary representation
+/// the first byte is the version of the format (`PATH_FORMAT_VERSION`)
+/// followed by one byte per move (9 kinds times 3 amplitudes fits in 5 bits)
+/// the output can be turned back into a path with `deserialize_path`
+pub fn serialize_path(path: &[MoveDescription]) -> Vec<u8>
+{
+    let mut bytes = Vec::with_capacity(path.len() + 1);
+    bytes.push(PATH_FORMAT_VERSION);
+    bytes.extend(path.iter()
+                     .map(|m| (m.kind as usize * Amplitude::VARIANT_COUNT + m.amplitude as usize) as u8));
+    bytes
+}
+
+/// turns bytes produced by `serialize_path` back into a path
+pub fn deserialize_path(bytes: &[u8]) -> Result<Vec<MoveDescription>, PathFormatError>
+{
+    match bytes.split_first()
+    {
+        None => Err(PathFormatError::Empty),
+        Some((&PATH_FORMAT_VERSION, moves)) =>
+        {
+            moves.iter()
+                 .enumerate()
+                 .map(|(index, &byte)| {
+                     // the bytes are the positions of the moves in `MoveDescription::all`
+                     MoveDescription::all().nth(byte as usize)
+                                           .ok_or(PathFormatError::InvalidMove { index, byte })
+                 })
+                 .collect()
+        }
+        Some((&version, _)) => Err(PathFormatError::UnknownVersion(version))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+    use crate::cube::Cube;
+
+    /// produces a random path, without any constraint on consecutive moves so that there is plenty to simplify
+    fn random_path(rng: &mut StdRng, length: usize) -> Vec<MoveDescription>
+    {
+        (0..length).map(|_| MoveDescription::all().choose(rng).unwrap()).collect()
+    }
+
+    /// applying a path then its inverse gives back the starting cube
+    #[test]
+    fn invert_path_undoes_path()
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..50
+        {
+            let start = Cube::solved().apply_path(&random_path(&mut rng, 20));
+            let path = random_path(&mut rng, 20);
+            assert!(start.apply_path(&path).apply_path(&invert_path(&path)) == start);
+            assert!(invert_path(&invert_path(&path)) == path);
+        }
+    }
+
+    /// a simplified path is never longer and has the same effect on a cube
+    #[test]
+    fn simplify_path_preserves_cube()
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        for length in 0..200
+        {
+            let path = random_path(&mut rng, length % 20);
+            let simplified = simplify_path(&path);
+            assert!(simplified.len() <= path.len());
+            assert!(Cube::solved().apply_path(&path) == Cube::solved().apply_path(&simplified),
+                    "{:?} was simplified into {:?}",
+                    path,
+                    simplified);
+        }
+    }
+
+    /// consecutive moves of the same kind are merged, simplifying twice changes nothing
+    #[test]
+    fn simplify_path_consecutive_moves()
+    {
+        let simplify = |path| simplify_path(&Cube::parse_path(path).unwrap());
+        assert!(simplify("R R") == Cube::parse_path("R2").unwrap());
+        assert!(simplify("R2 R") == Cube::parse_path("R'").unwrap());
+        assert!(simplify("U R R' U'").is_empty());
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100
+        {
+            let simplified = simplify_path(&random_path(&mut rng, 20));
+            assert!(simplify_path(&simplified) == simplified);
+        }
+    }
+
+    /// moves separated by parallel layers are merged
+    #[test]
+    fn simplify_path_parallel_layers()
+    {
+        let simplify = |path| simplify_path(&Cube::parse_path(path).unwrap());
+        assert!(simplify("R L R'") == Cube::parse_path("L").unwrap());
+        assert!(simplify("U E D U'") == Cube::parse_path("E D").unwrap());
+        assert!(simplify("R R' U").len() == 1);
+    }
+}
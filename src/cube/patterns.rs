@@ -0,0 +1,102 @@
+//! Well known patterns, obtained by applying a sequence of moves to a solved cube
+//!
+//! The sequences are written in the Singmaster notation, as parsed by `Cube::parse_path`.
+//!
+//! See this website for pictures of the patterns:
+//! https://ruwix.com/the-rubiks-cube/rubiks-cube-patterns-algorithms/
+use super::Cube;
+
+/// every face alternates between its color and the color of the opposite face
+pub const CHECKERBOARD: &str = "M2 E2 S2";
+/// a 2x2x2 cube of a different color nested in a corner of the cube
+pub const CUBE_IN_CUBE: &str = "F L F U' R U F2 L2 U' L' B D' B' L2 U";
+/// all middles are in place but flipped
+/// it was the first cube proven to require 20 moves in the half turn metric
+pub const SUPERFLIP: &str = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+/// length of an optimal solution of the superflip, in the half turn metric
+pub const SUPERFLIP_DISTANCE: usize = 20;
+
+/// applies a sequence of moves to a solved cube
+fn from_sequence(sequence: &str) -> Cube
+{
+    let path = Cube::parse_path(sequence).expect("patterns: unable to parse the sequence of moves");
+    Cube::solved().apply_path(&path)
+}
+
+/// returns a cube with a checkerboard pattern on all faces (see `CHECKERBOARD`)
+pub fn checkerboard() -> Cube
+{
+    from_sequence(CHECKERBOARD)
+}
+
+/// returns a cube with a smaller cube nested in one of its corners (see `CUBE_IN_CUBE`)
+pub fn cube_in_cube() -> Cube
+{
+    from_sequence(CUBE_IN_CUBE)
+}
+
+/// returns a cube with all middles flipped in place (see `SUPERFLIP`)
+/// it is at distance 20 from a solved cube, the maximum possible in the half turn metric
+pub fn superflip() -> Cube
+{
+    from_sequence(SUPERFLIP)
+}
+
+impl Cube
+{
+    /// returns a cube with all middles flipped in place
+    /// see `patterns::superflip`
+    pub fn superflip() -> Cube
+    {
+        superflip()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::Path;
+    use super::*;
+    use crate::cube::cubies::{Cubies, NB_MIDDLES};
+    use crate::solver::heuristic::{Heuristic, KorfHeuristic};
+
+    /// the superflip leaves all pieces in place, with the corners solved and all the middles flipped
+    #[test]
+    fn superflip_pieces()
+    {
+        let cube = superflip();
+        assert_eq!(cube.is_valid(), Ok(()));
+        let cubies = Cubies::from_cube(&cube).unwrap();
+        let solved = Cubies::solved();
+        assert_eq!(cubies.corner_permutation, solved.corner_permutation);
+        assert_eq!(cubies.corner_orientation, solved.corner_orientation);
+        assert_eq!(cubies.middle_permutation, solved.middle_permutation);
+        assert_eq!(cubies.middle_orientation, [1; NB_MIDDLES]);
+    }
+
+    /// all patterns can be solved and are not solved already
+    #[test]
+    fn patterns_are_valid()
+    {
+        for cube in [checkerboard(), cube_in_cube(), superflip()]
+        {
+            assert_eq!(cube.is_valid(), Ok(()));
+            assert!(!cube.is_solved());
+        }
+    }
+
+    /// the superflip is at distance 20 in the half turn metric
+    /// ignored by default: it needs the Korf tables (built with `deepcube gen-tables`)
+    /// and a search at depth 20 takes hours
+    /// the tables are read from the `DEEPCUBE_DATA` folder, or from `./data` if it is not set
+    #[test]
+    #[ignore]
+    fn superflip_distance()
+    {
+        let data = std::env::var("DEEPCUBE_DATA").unwrap_or_else(|_| String::from("./data"));
+        let file_name = Path::new(&data).join("korf_heuristic.bin");
+        let heuristic =
+            KorfHeuristic::load(&file_name.to_string_lossy()).expect("the Korf tables are missing");
+        assert_eq!(superflip().optimal_distance(&heuristic), Some(SUPERFLIP_DISTANCE as u8));
+    }
+}
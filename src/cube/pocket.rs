@@ -0,0 +1,42 @@
+//! The 2x2 pocket cube
+//!
+//! A pocket cube is a Rubik's cube without middles and centers,
+//! it can thus be studied as the corners of a Rubik's cube
+use super::{CubeOfSize, Cube};
+use super::moves::MoveOfSize;
+use super::sizes::{NB_SQUARES_SIDE, NB_SQUARES_SIDE_POCKET, NB_SQUARES_POCKET_CUBE};
+use super::coordinates::{Coordinate1D, Coordinate2D};
+
+/// A pocket cube (2x2)
+pub type PocketCube = CubeOfSize<NB_SQUARES_SIDE_POCKET, NB_SQUARES_POCKET_CUBE>;
+
+/// a way to twist a pocket cube
+pub type PocketMove = MoveOfSize<NB_SQUARES_SIDE_POCKET, NB_SQUARES_POCKET_CUBE>;
+
+/// takes the index of a square of a pocket cube
+/// and returns the index of the corner square of a Rubik's cube at the same place
+pub fn to_corner_square(index: usize) -> usize
+{
+    let coordinate = Coordinate1D::<NB_SQUARES_SIDE_POCKET> { x: index }.to_2D();
+    let scale = (NB_SQUARES_SIDE - 1) / (NB_SQUARES_SIDE_POCKET - 1);
+    let corner = Coordinate2D::<NB_SQUARES_SIDE> { face: coordinate.face,
+                                                   x: coordinate.x * scale,
+                                                   y: coordinate.y * scale };
+    corner.to_1D().x
+}
+
+impl PocketCube
+{
+    /// produces a Rubik's cube whose corners are the squares of the pocket cube
+    /// the middles and centers are those of the solved Rubik's cube,
+    /// the corners are thus only meaningful if the pocket cube is in the reference orientation
+    pub fn to_corners_cube(&self) -> Cube
+    {
+        let mut cube = Cube::solved();
+        for (index, color) in self.squares.iter().enumerate()
+        {
+            cube.squares[to_corner_square(index)] = *color;
+        }
+        cube
+    }
+}
@@ -0,0 +1,90 @@
+//! Expression of a cube relative to another cube
+//!
+//! Each square of a cube is identified by the square it occupies once the cube is solved
+//! (found using the colors of its piece, which are all different on the cubes we manipulate).
+//! Relabeling the colors of a cube such that a target cube would become solved
+//! turns the problem of going from a cube to the target into the problem of solving a cube.
+use super::CubeOfSize;
+use super::coordinates::Coordinate1D;
+
+impl<const SIDE: usize, const NB_SQUARES: usize> CubeOfSize<SIDE, NB_SQUARES>
+{
+    /// relabels the colors of the cube such that `target` would become solved
+    /// the result is solved if and only if the cube is equal to `target` up to a rotation of the whole cube
+    /// and applying a move to the cube or to the result leads to the same relabeled cube
+    /// returns None if the cube or the target is not made of the pieces of a solved cube
+    pub fn relative_to(&self, target: &Self) -> Option<Self>
+    {
+        let solved = Self::solved();
+        let pieces = pieces::<SIDE, NB_SQUARES>();
+        let squares_home = self.squares_home(&solved, &pieces)?;
+        let target_home = target.squares_home(&solved, &pieces)?;
+        // index of the square, in the target, holding a given square of the solved cube
+        let mut index_in_target = [0; NB_SQUARES];
+        for (index, home) in target_home.iter().enumerate()
+        {
+            index_in_target[*home] = index;
+        }
+        // each square takes the color its home position would have if the target was solved
+        let mut result = solved.clone();
+        for (color, home) in result.squares.iter_mut().zip(squares_home.iter())
+        {
+            *color = solved.squares[index_in_target[*home]];
+        }
+        Some(result)
+    }
+
+    /// for each square, returns the index of the square it occupies once the cube is solved
+    /// returns None if a piece does not exist on the solved cube or appears several times
+    fn squares_home(&self, solved: &Self, pieces: &[Vec<usize>]) -> Option<[usize; NB_SQUARES]>
+    {
+        let mut result = [0; NB_SQUARES];
+        let mut is_home_used = [false; NB_SQUARES];
+        for piece in pieces
+        {
+            let mask = colors_mask(self, piece);
+            let solved_piece = pieces.iter().find(|solved_piece| colors_mask(solved, solved_piece) == mask)?;
+            for index in piece
+            {
+                let color = self.squares[*index];
+                let home = *solved_piece.iter().find(|home| solved.squares[**home] == color)?;
+                if is_home_used[home]
+                {
+                    return None;
+                }
+                is_home_used[home] = true;
+                result[*index] = home;
+            }
+        }
+        Some(result)
+    }
+}
+
+/// groups the indexes of the squares by piece
+fn pieces<const SIDE: usize, const NB_SQUARES: usize>() -> Vec<Vec<usize>>
+{
+    let mut positions: Vec<[usize; 3]> = Vec::new();
+    let mut pieces: Vec<Vec<usize>> = Vec::new();
+    for x in 0..NB_SQUARES
+    {
+        let position = Coordinate1D::<SIDE> { x }.piece_position();
+        match positions.iter().position(|p| *p == position)
+        {
+            Some(piece) => pieces[piece].push(x),
+            None =>
+            {
+                positions.push(position);
+                pieces.push(vec![x]);
+            }
+        }
+    }
+    pieces
+}
+
+/// returns a mask with one bit set per color of the given squares
+fn colors_mask<const SIDE: usize, const NB_SQUARES: usize>(cube: &CubeOfSize<SIDE, NB_SQUARES>,
+                                                             piece: &[usize])
+                                                             -> usize
+{
+    piece.iter().fold(0, |mask, index| mask | (1 << (cube.squares[*index] as usize)))
+}
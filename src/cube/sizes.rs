@@ -11,3 +11,9 @@ pub const NB_FACES: usize = 6;
 
 /// Total number of squares on a cube
 pub const NB_SQUARES_CUBE: usize = NB_FACES * NB_SQUARES_FACE;
+
+/// Number of squares on the side of a pocket cube (2x2)
+pub const NB_SQUARES_SIDE_POCKET: usize = 2;
+
+/// Total number of squares on a pocket cube
+pub const NB_SQUARES_POCKET_CUBE: usize = NB_FACES * NB_SQUARES_SIDE_POCKET * NB_SQUARES_SIDE_POCKET;
@@ -0,0 +1,97 @@
+//! Symmetries of the cube (rotations and reflections)
+//!
+//! Conjugating a cube by a symmetry (turning it in space then renaming its colors so that the solved cube stays solved)
+//! gives a cube that is exactly as far from being solved, which lets us store a single distance per group of cubes.
+use std::collections::BTreeSet;
+use enum_iterator::IntoEnumIterator;
+use serde::{Serialize, Deserialize};
+use super::{Cube, Color, NB_COLORS, NB_SQUARES_CUBE};
+use super::coordinates::{Coordinate1D, RotationAxis};
+
+/// number of symmetries of the cube: 24 rotations, each of which can be followed by a reflection
+pub const NB_SYMMETRIES: usize = 48;
+
+/// a symmetry of the cube
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Symmetry
+{
+    /// index to which each square is sent
+    #[serde(with = "serde_arrays")]
+    permutation: [usize; NB_SQUARES_CUBE],
+    /// color to which each color is renamed (the color of the face to which its face is sent)
+    colors: [Color; NB_COLORS]
+}
+
+impl Symmetry
+{
+    /// builds a symmetry from the index to which each square is sent
+    fn from_permutation(permutation: [usize; NB_SQUARES_CUBE]) -> Symmetry
+    {
+        // a solved cube should be sent to itself
+        let solved = Cube::solved();
+        let mut colors = [Color::Invalid; NB_COLORS];
+        for (index, color) in solved.squares.iter().enumerate()
+        {
+            colors[*color as usize] = solved.squares[permutation[index]];
+        }
+        Symmetry { permutation, colors }
+    }
+
+    /// returns all the symmetries of the cube, starting with the identity
+    pub fn all() -> Vec<Symmetry>
+    {
+        // computes all rotations by rotating the identity until no new permutation appears
+        let identity: [usize; NB_SQUARES_CUBE] = std::array::from_fn(|index| index);
+        let mut rotations = vec![identity];
+        let mut known_rotations = BTreeSet::from([identity]);
+        let mut index_rotation = 0;
+        while index_rotation < rotations.len()
+        {
+            let rotation = rotations[index_rotation];
+            for axis in RotationAxis::into_enum_iter()
+            {
+                let new_rotation = rotation.map(|index| Coordinate1D::new(index).rotate(axis).x);
+                if known_rotations.insert(new_rotation)
+                {
+                    rotations.push(new_rotation);
+                }
+            }
+            index_rotation += 1;
+        }
+        // adds a reflection to each rotation
+        let reflections: Vec<_> =
+            rotations.iter().map(|rotation| rotation.map(|index| Coordinate1D::new(index).mirror().x)).collect();
+        rotations.into_iter().chain(reflections).map(Symmetry::from_permutation).collect()
+    }
+
+    /// returns the index to which the given square is sent
+    pub fn map_square(&self, index: usize) -> usize
+    {
+        self.permutation[index]
+    }
+
+    /// returns the color to which the given color is renamed
+    pub fn map_color(&self, color: Color) -> Color
+    {
+        match color
+        {
+            Color::Invalid => Color::Invalid,
+            color => self.colors[color as usize]
+        }
+    }
+}
+
+impl Cube
+{
+    /// conjugates the cube by the given symmetry
+    /// the resulting cube is exactly as far from being solved as the original cube
+    pub fn conjugate(&self, symmetry: &Symmetry) -> Cube
+    {
+        let mut squares = [Color::Invalid; NB_SQUARES_CUBE];
+        for (index, color) in self.squares.iter().enumerate()
+        {
+            squares[symmetry.map_square(index)] = symmetry.map_color(*color);
+        }
+        Cube { squares }
+    }
+}
@@ -0,0 +1,69 @@
+//! A cube that remembers the moves applied to it
+use super::{CubeOfSize, NB_SQUARES_SIDE, NB_SQUARES_CUBE};
+use super::moves::{MoveDescription, MoveTableOfSize};
+
+/// a cube along with the history of the moves applied to it
+/// lets interactive front-ends step forward and backward through moves
+pub struct TrackedCubeOfSize<const SIDE: usize, const NB_SQUARES: usize>
+{
+    cube: CubeOfSize<SIDE, NB_SQUARES>,
+    history: Vec<MoveDescription>,
+    /// used to turn descriptions into moves
+    table: MoveTableOfSize<SIDE, NB_SQUARES>
+}
+
+/// tracked Rubik's cube (3x3)
+pub type TrackedCube = TrackedCubeOfSize<NB_SQUARES_SIDE, NB_SQUARES_CUBE>;
+
+impl<const SIDE: usize, const NB_SQUARES: usize> TrackedCubeOfSize<SIDE, NB_SQUARES>
+{
+    /// starts tracking the given cube, with an empty history
+    pub fn new(cube: CubeOfSize<SIDE, NB_SQUARES>) -> Self
+    {
+        TrackedCubeOfSize { cube, history: Vec::new(), table: MoveTableOfSize::new() }
+    }
+
+    /// applies a move to the cube and records it
+    /// panics if the move is not available for this size of cube
+    pub fn apply(&mut self, m: MoveDescription)
+    {
+        self.cube = self.cube.apply_move(self.table.get(&m));
+        self.history.push(m);
+    }
+
+    /// applies all the moves of a path, one after the other
+    pub fn apply_path(&mut self, path: &[MoveDescription])
+    {
+        for m in path
+        {
+            self.apply(*m);
+        }
+    }
+
+    /// undoes the last move, by applying its inverse, and returns it
+    /// returns None if there is no move left to undo
+    pub fn undo(&mut self) -> Option<MoveDescription>
+    {
+        let m = self.history.pop()?;
+        self.cube = self.cube.apply_move(self.table.get(&m.inverse()));
+        Some(m)
+    }
+
+    /// moves applied to the cube so far, oldest first
+    pub fn history(&self) -> &[MoveDescription]
+    {
+        &self.history
+    }
+
+    /// the cube in its current state
+    pub fn cube(&self) -> &CubeOfSize<SIDE, NB_SQUARES>
+    {
+        &self.cube
+    }
+
+    /// stops tracking the cube and returns it
+    pub fn into_cube(self) -> CubeOfSize<SIDE, NB_SQUARES>
+    {
+        self.cube
+    }
+}
@@ -0,0 +1,19 @@
+//! Rubik's cube representation, heuristics and (optimal) solvers
+//!
+//! the public API is made of:
+//! - `cube`: the cubes (`Cube`, `CubeOfSize`, `PocketCube`), the moves (`Move`, `MoveDescription`) and paths
+//! - `solver::heuristic`: the `Heuristic` trait and the heuristics (precomputed tables and their combinations)
+//! - `solver`: the solvers, implemented as `solve_*` methods on the cubes, and their statistics
+//!
+//! the most common types are also reexported at the root of the crate
+#![allow(dead_code, non_snake_case)]
+mod utils;
+pub mod cube;
+pub mod solver;
+pub use crate::cube::{Cube, CubeOfSize, CubeIdentifier, PocketCube, Move, MoveKind, Metric};
+pub use crate::cube::moves::{MoveDescription, Amplitude};
+pub use crate::solver::{SolveStats, MoveCosts, ProgressSink, ProgressEvent, StdoutSink, NullSink,
+                        MAX_SOLUTION_LENGTH};
+pub use crate::solver::heuristic::{Heuristic, DynHeuristic, LoadError, SaveError, CornerHeuristic,
+                                   LowerMiddleHeuristic, UpperMiddleHeuristic, KorfHeuristic,
+                                   PackedKorfHeuristic, TwoPhaseTables, PocketHeuristic};
@@ -1,81 +1,287 @@
-#![allow(dead_code, non_snake_case)]
-mod utils;
-mod cube;
-mod solver;
-pub use crate::solver::heuristic::{Heuristic, KorfHeuristic, LowerMiddleHeuristic, UpperMiddleHeuristic,
-                               CornerHeuristic, SumHeuristic};
-
-// sets the allocator to jemalloc
+use clap::{Parser, Subcommand, ValueEnum, CommandFactory, error::ErrorKind};
+use deepcube::{cube, solver};
+use deepcube::cube::moves::MoveDescription;
+use deepcube::solver::heuristic::{LoadError, SaveError};
+use std::path::Path;
+use deepcube::solver::heuristic::{Heuristic, KorfHeuristic, LowerMiddleHeuristic, UpperMiddleHeuristic,
+                                  CornerHeuristic, PackedKorfHeuristic, TwoPhaseTables};
+
+// sets the allocator to jemalloc (unless the `jemalloc` feature is disabled, falling back to the system allocator)
+#[cfg(feature = "jemalloc")]
 #[global_allocator]
 static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
+/// command line interface, run `deepcube --help` to get the list of commands
+#[derive(Parser)]
+#[command(about = "Solves Rubik's cubes and builds the heuristic tables used to do so")]
+struct Cli
+{
+    /// action to be done
+    #[command(subcommand)]
+    runtype: RunType
+}
+
 /// describes the various type of run that could happen
+#[derive(Subcommand)]
 enum RunType
 {
     /// run various test/debug operations
-    TestRun,
+    TestRun
+    {
+        /// folder containing the tables built by `gen-tables`
+        #[arg(long, env = "DEEPCUBE_DATA", default_value = DEFAULT_DATA_FOLDER)]
+        data: String
+    },
     /// build heuristic tables for later use
-    GenerateHeuristicTables,
-    /// solve a random cube scrambled a given number of times
-    SolveCube(usize)
+    #[command(name = "gen-tables")]
+    GenerateHeuristicTables
+    {
+        /// folder in which the tables are saved
+        #[arg(long, env = "DEEPCUBE_DATA", default_value = DEFAULT_DATA_FOLDER)]
+        out: String
+    },
+    /// solve a cube, given by its scramble or scrambled randomly
+    #[command(name = "solve")]
+    SolveCube
+    {
+        /// moves used to scramble the cube (for example "R U R'"), a random scramble is used if absent
+        #[arg(long)]
+        scramble: Option<String>,
+        /// number of random moves applied to the cube when no scramble is given
+        #[arg(long, default_value_t = 200)]
+        nb_scramble: usize,
+        /// heuristic used by the algorithms that need one
+        #[arg(long, value_enum, default_value_t = HeuristicType::Korf)]
+        heuristic: HeuristicType,
+        /// algorithm used to solve the cube
+        #[arg(long, value_enum, default_value_t = Algorithm::IdastarParallel)]
+        algo: Algorithm,
+        /// folder containing the tables built by `gen-tables`
+        #[arg(long, env = "DEEPCUBE_DATA", default_value = DEFAULT_DATA_FOLDER)]
+        data: String
+    },
+    /// checks that the heuristic tables never exceed God's number
+    VerifyMaxDepth
+    {
+        /// folder containing the tables built by `gen-tables`
+        #[arg(long, env = "DEEPCUBE_DATA", default_value = DEFAULT_DATA_FOLDER)]
+        data: String
+    }
+}
+
+/// heuristics that can be loaded from the tables built by `gen-tables`
+#[derive(Clone, Copy, ValueEnum)]
+enum HeuristicType
+{
+    Korf,
+    PackedKorf,
+    Corners,
+    LowerMiddles,
+    UpperMiddles
+}
+
+/// algorithms that can be used to solve a cube
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Algorithm
+{
+    Bfs,
+    BidirectionalBfs,
+    IterativeDeepening,
+    BestFirst,
+    Astar,
+    Idastar,
+    IdastarParallel,
+    TwoPhase
+}
+
+/// folder containing the tables when neither the `--data` flag nor the `DEEPCUBE_DATA` environment variable are set
+const DEFAULT_DATA_FOLDER: &str = "./data";
+
+/// returns the path of the given table file inside the data folder
+fn data_file(data: &str, file_name: &str) -> String
+{
+    Path::new(data).join(file_name).to_string_lossy().into_owned()
+}
+
+/// returns the loaded tables or exits with a clear message if they could not be loaded
+fn load_or_exit<T>(result: Result<T, LoadError>) -> T
+{
+    result.unwrap_or_else(|error| {
+              eprintln!("error: {}", error);
+              eprintln!("the tables can be built with `deepcube gen-tables`, \
+                         their folder is set with `--data` or the `DEEPCUBE_DATA` environment variable");
+              std::process::exit(1)
+          })
+}
+
+/// exits with a clear message if the tables could not be saved
+fn save_or_exit(result: Result<(), SaveError>)
+{
+    if let Err(error) = result
+    {
+        eprintln!("error: {}", error);
+        std::process::exit(1)
+    }
+}
+
+/// loads the heuristic stored in the given file of the data folder
+/// exits with a clear message if it could not be loaded
+fn load_heuristic<H: Heuristic>(data: &str, file_name: &str) -> H
+{
+    load_or_exit(H::load(&data_file(data, file_name)))
+}
+
+/// solves the cube with the given algorithm, loading the heuristic or tables it needs from the `data` folder
+fn solve(cube: &cube::Cube,
+         algo: Algorithm,
+         heuristic: HeuristicType,
+         data: &str)
+         -> Option<Vec<MoveDescription>>
+{
+    match algo
+    {
+        Algorithm::Bfs => cube.solve_breath_first_search(),
+        Algorithm::BidirectionalBfs => cube.solve_bidirectional_bfs(),
+        Algorithm::IterativeDeepening => cube.solve_iterative_deepening(),
+        Algorithm::TwoPhase =>
+        {
+            let tables = load_or_exit(TwoPhaseTables::load(&data_file(data, "two_phase_tables.bin")));
+            cube.solve_two_phase(&tables)
+        }
+        _ => match heuristic
+        {
+            HeuristicType::Korf =>
+            {
+                let heuristic: KorfHeuristic = load_heuristic(data, "korf_heuristic.bin");
+                solve_with_heuristic(cube, algo, &heuristic)
+            }
+            HeuristicType::PackedKorf =>
+            {
+                let heuristic: PackedKorfHeuristic = load_heuristic(data, "packed_korf_heuristic.bin");
+                solve_with_heuristic(cube, algo, &heuristic)
+            }
+            HeuristicType::Corners =>
+            {
+                let heuristic: CornerHeuristic = load_heuristic(data, "corners_heuristic.bin");
+                solve_with_heuristic(cube, algo, &heuristic)
+            }
+            HeuristicType::LowerMiddles =>
+            {
+                let heuristic: LowerMiddleHeuristic = load_heuristic(data, "lower_middles_heuristic.bin");
+                solve_with_heuristic(cube, algo, &heuristic)
+            }
+            HeuristicType::UpperMiddles =>
+            {
+                let heuristic: UpperMiddleHeuristic = load_heuristic(data, "upper_middles_heuristic.bin");
+                solve_with_heuristic(cube, algo, &heuristic)
+            }
+        }
+    }
+}
+
+/// solves the cube with one of the algorithms using an heuristic
+fn solve_with_heuristic<H: Heuristic + Sync>(cube: &cube::Cube,
+                                             algo: Algorithm,
+                                             heuristic: &H)
+                                             -> Option<Vec<MoveDescription>>
+{
+    match algo
+    {
+        Algorithm::BestFirst => Some(cube.solve_best_first_search(heuristic)),
+        Algorithm::Astar => cube.solve_Astar(heuristic),
+        Algorithm::Idastar => cube.solve_iterative_deepening_Astar(heuristic),
+        Algorithm::IdastarParallel => cube.solve_iterative_deepening_Astar_parallel(heuristic),
+        _ => unreachable!("{:?} does not use an heuristic", algo)
+    }
 }
 
 fn main()
 {
     // action to be done when running code
-    //let runtype = RunType::GenerateHeuristicTables;
-    let runtype = RunType::SolveCube(200);
+    let runtype = Cli::parse().runtype;
 
     match runtype
     {
-        RunType::SolveCube(nb_scramble) =>
+        RunType::SolveCube { scramble, nb_scramble, heuristic, algo, data } =>
         {
             // generate a scrambled cube
-            let cube = cube::Cube::scrambled(nb_scramble);
+            let cube = match scramble
+            {
+                Some(scramble) => match cube::Cube::parse_path(&scramble)
+                {
+                    Ok(path) => cube::Cube::solved().apply_path(&path),
+                    Err(error) => Cli::command().error(ErrorKind::ValueValidation, error).exit()
+                },
+                None => cube::Cube::scrambled(nb_scramble)
+            };
             println!("Scrambled cube:");
             cube.display();
 
-            // gets an heuristic
-            //let heuristic = CornerHeuristic::load("./data/corners_heuristic.bin");
-            //let heuristic = LowerMiddleHeuristic::load("./data/lower_middles_heuristic.bin");
-            let heuristic = KorfHeuristic::load("./data/korf_heuristic.bin");
-            //let heuristic = AverageHeuristic::load("./data/average_heuristic.bin");
-
             // solves the cube
-            //let path = cube.solve_breath_first_search();
-            //let path = cube.solve_best_first_search(&heuristic);
-            //let path = cube.solve_iterative_deepening();
-            let path = cube.solve_iterative_deepening_Astar(&heuristic);
-            let path = cube.solve_iterative_deepening_Astar_parallel(&heuristic);
+            let path = solve(&cube, algo, heuristic, &data);
+            let path = path.expect("the cube cannot be solved");
 
             // displays result
             println!("Solved cube:");
             let cube = cube.apply_path(&path);
             cube.display();
         }
-        RunType::GenerateHeuristicTables =>
+        RunType::GenerateHeuristicTables { out } =>
         {
+            // creates the output folder before spending hours on the tables
+            if let Err(error) = std::fs::create_dir_all(&out)
+            {
+                eprintln!("error: unable to create the folder '{}': {}", out, error);
+                std::process::exit(1)
+            }
             // saves corners heuristics
             let corners_heuristic = CornerHeuristic::new();
-            corners_heuristic.save("./data/corners_heuristic.bin");
-            //let corners_heuristic = CornerHeuristic::load("./data/corners_heuristic.bin");
+            save_or_exit(corners_heuristic.save(&data_file(&out, "corners_heuristic.bin")));
+            //let corners_heuristic: CornerHeuristic = load_heuristic(&out, "corners_heuristic.bin");
             // saves lower middles heuristics
             let lower_middles_heuristic = LowerMiddleHeuristic::new();
-            lower_middles_heuristic.save("./data/lower_middles_heuristic.bin");
-            //let lower_middles_heuristic = LowerMiddleHeuristic::load("./data/lower_middles_heuristic.bin");
+            save_or_exit(lower_middles_heuristic.save(&data_file(&out, "lower_middles_heuristic.bin")));
+            //let lower_middles_heuristic: LowerMiddleHeuristic = load_heuristic(&out, "lower_middles_heuristic.bin");
             // saves upper middles heuristics
             let upper_middles_heuristic = UpperMiddleHeuristic::new();
-            upper_middles_heuristic.save("./data/upper_middles_heuristic.bin");
-            //let upper_middles_heuristic = UpperMiddleHeuristic::load("./data/upper_middles_heuristic.bin");
+            save_or_exit(upper_middles_heuristic.save(&data_file(&out, "upper_middles_heuristic.bin")));
+            //let upper_middles_heuristic: UpperMiddleHeuristic = load_heuristic(&out, "upper_middles_heuristic.bin");
             // saves korf heuristics
             // built by recycling the previous two heuristics
             let korf_heuristic =
                 KorfHeuristic { corners_heuristic, lower_middles_heuristic, upper_middles_heuristic };
-            korf_heuristic.save("./data/korf_heuristic.bin");
+            save_or_exit(korf_heuristic.save(&data_file(&out, "korf_heuristic.bin")));
+            // saves a packed version of the korf heuristic, using half the memory
+            let packed_korf_heuristic = korf_heuristic.pack();
+            save_or_exit(packed_korf_heuristic.save(&data_file(&out, "packed_korf_heuristic.bin")));
             //let average_heuristic = SumHeuristic { corners_heuristic, lower_middles_heuristic, upper_middles_heuristic };
-            //average_heuristic.save("./data/sum_heuristic.bin");
+            //save_or_exit(average_heuristic.save(&data_file(&out, "sum_heuristic.bin")));
+            // saves the tables of the two-phase algorithm
+            let two_phase_tables = TwoPhaseTables::new();
+            save_or_exit(two_phase_tables.save(&data_file(&out, "two_phase_tables.bin")));
         }
-        RunType::TestRun =>
+        RunType::VerifyMaxDepth { data } =>
+        {
+            let heuristic: KorfHeuristic = load_heuristic(&data, "korf_heuristic.bin");
+            // exhaustive check of the tables
+            let max_table_distance = heuristic.max_distance();
+            println!("Maximum distance in the tables: {}", max_table_distance);
+            // check on random cubes
+            let max_sampled_distance = cube::Cube::max_optimal_depth(&heuristic, 100_000);
+            println!("Maximum distance on random cubes: {}", max_sampled_distance);
+            // check on the superflip, whose optimal distance is known
+            // (the bound is far from it: its corners are solved and each middles table only sees half of its middles)
+            let superflip_distance = heuristic.optimistic_distance_to_solved(&cube::Cube::superflip());
+            println!("Lower bound on the distance of the superflip: {} (actual distance: {})",
+                     superflip_distance,
+                     cube::patterns::SUPERFLIP_DISTANCE);
+            assert!((max_table_distance as usize) <= solver::MAX_SOLUTION_LENGTH,
+                    "the tables are not admissible as they exceed God's number");
+            assert!((superflip_distance as usize) <= cube::patterns::SUPERFLIP_DISTANCE,
+                    "the tables are not admissible as they overestimate the distance of the superflip");
+            println!("The tables are consistent with God's number ({})", solver::MAX_SOLUTION_LENGTH);
+        }
+        RunType::TestRun { data } =>
         {
             // test the display function
             let cube = cube::Cube::solved();
@@ -108,9 +314,17 @@ fn main()
                 cube.apply_move(&m).display();
             }*/
 
+            // solves a pocket cube using the corners heuristic
+            /*let pocket_cube = cube::PocketCube::scrambled(200);
+            println!("Scrambled pocket cube:");
+            pocket_cube.display();
+            let pocket_heuristic = PocketHeuristic::new(load_heuristic::<CornerHeuristic>(&data, "corners_heuristic.bin"));
+            let path = pocket_cube.solve_iterative_deepening_Astar(&pocket_heuristic).unwrap();
+            pocket_cube.apply_path(&path).display();*/
+
             // generate the heuristics
             let corners_heuristic = CornerHeuristic::new();
-            corners_heuristic.save("./data/corners_heuristic.bin");
+            save_or_exit(corners_heuristic.save(&data_file(&data, "corners_heuristic.bin")));
         }
     }
 }
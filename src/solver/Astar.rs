@@ -0,0 +1,192 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::time::Duration;
+use stopwatch::Stopwatch;
+use crate::cube::moves::{Move, MoveDescription};
+use crate::cube::{Cube, CubeIdentifier};
+use crate::solver::heuristic::{Heuristic, DynHeuristic, DynHeuristicRef};
+use super::iterative_deepening_Astar::is_redundant;
+use super::{is_out_of_time, already_solved, MAX_SOLUTION_LENGTH};
+use super::stats::{SolveStats, print_result};
+use super::{ProgressSink, ProgressEvent, StdoutSink};
+
+/// information stored for each cube seen by the search
+struct Node
+{
+    /// length of the shortest known path from the starting cube to this cube
+    depth: usize,
+    /// cube from which we reached this cube and the move used, None for the starting cube
+    parent: Option<(CubeIdentifier, MoveDescription)>,
+    /// true once the cube has been expanded, its depth cannot be improved anymore
+    is_expanded: bool
+}
+
+/// cube waiting to be expanded
+/// ordered by smallest estimated total length first, then deepest first (to dive toward a solution)
+type OpenCube = Reverse<(usize, Reverse<usize>, Cube)>;
+
+/// rebuilds the path going from the starting cube to the given cube by following the parents
+fn rebuild_path(nodes: &BTreeMap<CubeIdentifier, Node>, identifier: CubeIdentifier) -> Vec<MoveDescription>
+{
+    let mut path = Vec::new();
+    let mut identifier = identifier;
+    while let Some((parent, description)) = nodes[&identifier].parent
+    {
+        path.push(description);
+        identifier = parent;
+    }
+    path.reverse();
+    path
+}
+
+impl Cube
+{
+    /// solves the given cube with an A* search, expanding the most promising cube first
+    /// NOTE:
+    /// - this algorithm *will* find an optimal solution as long as the heuristic is consistent
+    ///   (which is the case of all the table heuristics and of their maximum)
+    /// - unlike `solve_iterative_deepening_Astar`, each cube is expanded at most once
+    ///   which means that it expands far fewer cubes
+    ///
+    /// WARNING: this algorithm stores all the cubes it has seen, it will fill the memory on hard cubes
+    /// prefer `solve_iterative_deepening_Astar` (which uses almost no memory) unless you have a lot of RAM
+    ///
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_Astar<H: Heuristic>(&self, heuristic: &H) -> Option<Vec<MoveDescription>>
+    {
+        self.solve_Astar_timed(heuristic, Duration::MAX)
+    }
+
+    /// solves the given cube with an A* search, expanding the most promising cube first
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_Astar_timed<H: Heuristic>(&self,
+                                           heuristic: &H,
+                                           timeout: Duration)
+                                           -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.Astar(heuristic, timeout, &StdoutSink);
+        print_result(path, &stats, timeout, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube with an A* search, expanding the most promising cube first
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_Astar_with_stats<H: Heuristic, S: ProgressSink>(&self,
+                                                                 heuristic: &H,
+                                                                 sink: &S)
+                                                                 -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        self.Astar(heuristic, Duration::MAX, sink)
+    }
+
+    /// solves the given cube with an A* search, using a heuristic chosen at runtime
+    /// see `solve_Astar`
+    pub fn solve_Astar_dyn(&self, heuristic: &dyn DynHeuristic) -> Option<Vec<MoveDescription>>
+    {
+        self.solve_Astar(&DynHeuristicRef(heuristic))
+    }
+
+    /// A* search
+    /// returns None if no solution was found in less than `timeout` or in less than `MAX_SOLUTION_LENGTH` moves
+    fn Astar<H: Heuristic, S: ProgressSink>(&self,
+                                            heuristic: &H,
+                                            timeout: Duration,
+                                            sink: &S)
+                                            -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
+        // used to time the computation
+        let timer = Stopwatch::start_new();
+        let mut nb_cube_expanded = 0;
+        let mut nb_heuristic_calls = 1;
+        // all moves that can be applied to a cube
+        let moves = Move::all_moves();
+        // a heuristic that overestimates distances might need to search deeper to find a solution
+        let max_estimated_length =
+            ((MAX_SOLUTION_LENGTH as f32) * heuristic.overestimation_factor()).ceil() as usize;
+
+        // all cubes seen so far, identified by their identifier
+        let mut nodes = BTreeMap::new();
+        nodes.insert(self.to_identifier(), Node { depth: 0, parent: None, is_expanded: false });
+        // cubes waiting to be expanded
+        let mut open_cubes: BinaryHeap<OpenCube> = BinaryHeap::new();
+        let estimated_length = heuristic.optimistic_distance_to_solved(self) as usize;
+        open_cubes.push(Reverse((estimated_length, Reverse(0), self.clone())));
+        // largest estimated length expanded so far, used to report progress
+        let mut current_estimated_length = estimated_length;
+
+        while let Some(Reverse((estimated_length, Reverse(depth), cube))) = open_cubes.pop()
+        {
+            // stops if we ran out of time or proved that there is no solution short enough
+            if is_out_of_time(&timer, timeout, nb_cube_expanded) || (estimated_length > max_estimated_length)
+            {
+                break;
+            }
+            // skips the cube if it was already expanded or if a shorter path to it was found since
+            let identifier = cube.to_identifier();
+            let node = nodes.get_mut(&identifier).unwrap();
+            if node.is_expanded || (node.depth < depth)
+            {
+                continue;
+            }
+            node.is_expanded = true;
+            let previous_kind = node.parent.map(|(_, description)| description.kind);
+            // returns the path if the cube is solved
+            if cube.is_solved()
+            {
+                let path = rebuild_path(&nodes, identifier);
+                let stats = SolveStats { path_length: Some(depth),
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls,
+                                         nb_transposition_hits: 0,
+                                         max_depth_reached: estimated_length,
+                                         elapsed: timer.elapsed() };
+                return (Some(path), stats);
+            }
+            // reports information on the run when all cubes with a smaller estimated length have been expanded
+            if estimated_length > current_estimated_length
+            {
+                sink.report(ProgressEvent::DepthCompleted { solver: "A*",
+                                                            depth: current_estimated_length,
+                                                            nb_cube_expanded,
+                                                            nb_heuristic_calls,
+                                                            elapsed: timer.elapsed() });
+                current_estimated_length = estimated_length;
+            }
+            // expands the cube
+            nb_cube_expanded += 1;
+            let child_depth = depth + 1;
+            for m in moves.iter().filter(|m| !is_redundant(previous_kind, m.description.kind))
+            {
+                let child = cube.apply_move(m);
+                let child_identifier = child.to_identifier();
+                let child_node = Node { depth: child_depth,
+                                        parent: Some((identifier, m.description)),
+                                        is_expanded: false };
+                // only keeps the child if it is new or if we found a shorter path to it
+                let is_known = nodes.get(&child_identifier)
+                                    .is_some_and(|node| node.is_expanded || (node.depth <= child_depth));
+                if is_known
+                {
+                    continue;
+                }
+                nodes.insert(child_identifier, child_node);
+                nb_heuristic_calls += 1;
+                let child_distance = heuristic.optimistic_distance_to_solved(&child) as usize;
+                let child_estimated_length = child_depth + child_distance;
+                open_cubes.push(Reverse((child_estimated_length, Reverse(child_depth), child)));
+            }
+        }
+
+        let stats = SolveStats { path_length: None,
+                                 nb_cube_expanded,
+                                 nb_heuristic_calls,
+                                 nb_transposition_hits: 0,
+                                 max_depth_reached: current_estimated_length,
+                                 elapsed: timer.elapsed() };
+        (None, stats)
+    }
+}
@@ -1,18 +1,76 @@
+use std::time::Duration;
 use stopwatch::Stopwatch;
 use crate::cube::moves::{Move, MoveDescription};
 use crate::cube::Cube;
-use crate::solver::heuristic::Heuristic;
+use crate::solver::heuristic::{Heuristic, DynHeuristic, DynHeuristicRef};
+use super::{is_out_of_time, already_solved};
+use super::cubeset::CubeSet;
+use super::stats::{SolveStats, print_result};
+use super::{ProgressSink, ProgressEvent, StdoutSink};
+
+/// maximum number of visited cubes remembered by the best first search
+/// older cubes are forgotten once this is reached, which keeps the memory use bounded
+const MAX_NB_VISITED_CUBES: usize = 1_000_000;
 
 impl Cube
 {
     /// solves the given cube by using the most promising move greedily
     /// NOTE:
-    /// - this algorithm uses O(1) memory
+    /// - this algorithm uses a bounded amount of memory (see `MAX_NB_VISITED_CUBES`)
+    /// - it never goes back to a cube it remembers visiting, backtracking when all children have been visited
+    ///
     /// WARNINGS:
     /// - this algorithm might never find a solution
     /// - this algorithm might find a non-optimal solution
     pub fn solve_best_first_search<H: Heuristic>(&self, heuristic: &H) -> Vec<MoveDescription>
     {
+        self.solve_best_first_search_timed(heuristic, Duration::MAX).unwrap()
+    }
+
+    /// solves the given cube by using the most promising move greedily
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_best_first_search_timed<H: Heuristic>(&self,
+                                                       heuristic: &H,
+                                                       timeout: Duration)
+                                                       -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.best_first_search(heuristic, timeout, &StdoutSink);
+        // the search only stops without a solution if it runs out of time
+        print_result(path, &stats, timeout, usize::MAX)
+    }
+
+    /// solves the given cube by using the most promising move greedily
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_best_first_search_with_stats<H: Heuristic, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        sink: &S)
+        -> (Vec<MoveDescription>, SolveStats)
+    {
+        let (path, stats) = self.best_first_search(heuristic, Duration::MAX, sink);
+        (path.unwrap(), stats)
+    }
+
+    /// solves the given cube by using the most promising move greedily, using a heuristic chosen at runtime
+    /// see `solve_best_first_search`
+    pub fn solve_best_first_search_dyn(&self, heuristic: &dyn DynHeuristic) -> Vec<MoveDescription>
+    {
+        self.solve_best_first_search(&DynHeuristicRef(heuristic))
+    }
+
+    /// greedy best first search
+    /// returns None if no solution was found in less than `timeout`
+    fn best_first_search<H: Heuristic, S: ProgressSink>(&self,
+                                                        heuristic: &H,
+                                                        timeout: Duration,
+                                                        sink: &S)
+                                                        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
         // used to time the computation
         let timer = Stopwatch::start_new();
         let mut nb_cube_expanded = 0;
@@ -22,37 +80,81 @@ impl Cube
         // the final result
         let mut depth = 0;
         let mut path = Vec::new();
-        // the current cube
+        // the current cube and the cubes leading to it, used to backtrack
         let mut cube = self.clone();
+        let mut previous_cubes = Vec::new();
+        // cubes already visited, we do not go back to them in order to avoid looping
+        let mut visited_cubes = CubeSet::new(MAX_NB_VISITED_CUBES);
+        visited_cubes.insert(&cube);
 
         while !cube.is_solved()
         {
-            // display information on the run
-            println!("Best first search: did distance {} in {:?} ({} cubes expanded / {} heuristic call)",
-                     depth,
-                     timer.elapsed(),
-                     nb_cube_expanded,
-                     nb_heuristic_calls);
-            // finds the best child according to the heuristic
-            let (child, description) = moves.iter()
-                                            .map(|m| (cube.apply_move(m), m.description))
-                                            .min_by_key(|(c, _d)| heuristic.optimistic_distance_to_solved(c))
-                                            .unwrap();
-            // updates information
-            cube = child;
-            path.push(description);
-            depth += 1;
+            // reports information on the run
+            sink.report(ProgressEvent::DepthCompleted { solver: "Best first search",
+                                                        depth,
+                                                        nb_cube_expanded,
+                                                        nb_heuristic_calls,
+                                                        elapsed: timer.elapsed() });
+            // stops if we ran out of time
+            if is_out_of_time(&timer, timeout, nb_cube_expanded)
+            {
+                let stats = SolveStats { path_length: None,
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls,
+                                         nb_transposition_hits: 0,
+                                         max_depth_reached: depth,
+                                         elapsed: timer.elapsed() };
+                return (None, stats);
+            }
+            // finds the best unvisited child according to the heuristic, evaluating all children at once
+            let (children, descriptions): (Vec<Cube>, Vec<MoveDescription>) =
+                moves.iter()
+                     .map(|m| (cube.apply_move(m), m.description))
+                     .filter(|(c, _d)| !visited_cubes.contains(c))
+                     .unzip();
+            let distances = heuristic.optimistic_distance_to_solved_batch(&children);
             nb_cube_expanded += 1;
-            nb_heuristic_calls += moves.len();
+            nb_heuristic_calls += children.len();
+            let best_child = children.into_iter()
+                                     .zip(descriptions)
+                                     .zip(distances)
+                                     .min_by_key(|(_child, distance)| *distance)
+                                     .map(|(child, _distance)| child);
+            match best_child
+            {
+                Some((child, description)) =>
+                {
+                    // goes down to the child
+                    visited_cubes.insert(&child);
+                    previous_cubes.push(std::mem::replace(&mut cube, child));
+                    path.push(description);
+                    depth += 1;
+                }
+                None => match previous_cubes.pop()
+                {
+                    // all children have been visited, goes back to the parent
+                    Some(parent) =>
+                    {
+                        cube = parent;
+                        path.pop();
+                        depth -= 1;
+                    }
+                    // we are back to the starting cube, forgets the visited cubes and starts again
+                    None =>
+                    {
+                        visited_cubes.clear();
+                        visited_cubes.insert(&cube);
+                    }
+                }
+            }
         }
 
-        // displays information on the result
-        println!("Done! Found a path of length {} in {:?} ({} cubes expanded / {} heuristic call)",
-                 depth,
-                 timer.elapsed(),
-                 nb_cube_expanded,
-                 nb_heuristic_calls);
-        println!("Path: {:?}", path);
-        path
+        let stats = SolveStats { path_length: Some(depth),
+                                 nb_cube_expanded,
+                                 nb_heuristic_calls,
+                                 nb_transposition_hits: 0,
+                                 max_depth_reached: depth,
+                                 elapsed: timer.elapsed() };
+        (Some(path), stats)
     }
 }
@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, btree_map::Entry};
+use std::time::Duration;
+use stopwatch::Stopwatch;
+use crate::cube::moves::{Move, MoveDescription};
+use crate::cube::{Cube, Metric, invert_path};
+use super::{is_out_of_time, already_solved, max_solution_length, MAX_SOLUTION_LENGTH,
+            MAX_SOLUTION_LENGTH_QTM};
+use super::stats::{SolveStats, print_result};
+use super::{ProgressSink, ProgressEvent, StdoutSink};
+
+/// one of the two sides of a bidirectional search
+struct SearchSide
+{
+    /// path (from the starting cubes of the side) to all the cubes observed so far
+    /// NOTE: we store the cubes themselves rather than their identifiers
+    /// as cubes that differ by a whole-cube rotation can share an identifier
+    /// which would make it impossible to stitch the paths of both sides together
+    known_cubes: BTreeMap<Cube, Vec<MoveDescription>>,
+    /// cubes at the current distance
+    current_cubes: Vec<Cube>,
+    /// distance of the current cubes to the starting cubes of the side
+    current_distance: usize
+}
+
+impl SearchSide
+{
+    /// starts a search from the given cubes
+    fn new(cubes: Vec<Cube>) -> SearchSide
+    {
+        let known_cubes = cubes.iter().map(|cube| (cube.clone(), Vec::new())).collect();
+        SearchSide { known_cubes, current_cubes: cubes, current_distance: 0 }
+    }
+
+    /// returns the shortest path going through a cube known by both sides
+    /// the result is (path on this side, path on the other side)
+    fn shortest_meeting(&self,
+                        other: &SearchSide,
+                        cubes: &[Cube])
+                        -> Option<(Vec<MoveDescription>, Vec<MoveDescription>)>
+    {
+        cubes.iter()
+             .filter_map(|cube| {
+                 let path = self.known_cubes.get(cube)?;
+                 let other_path = other.known_cubes.get(cube)?;
+                 Some((path, other_path))
+             })
+             .min_by_key(|(path, other_path)| path.len() + other_path.len())
+             .map(|(path, other_path)| (path.clone(), other_path.clone()))
+    }
+
+    /// expands all the cubes at the current distance
+    /// the new cubes become the current cubes
+    /// returns false early if we ran out of time
+    fn expand(&mut self,
+              moves: &[Move],
+              nb_cube_expanded: &mut usize,
+              timer: &Stopwatch,
+              timeout: Duration)
+              -> bool
+    {
+        let mut new_cubes = Vec::new();
+        for cube in self.current_cubes.iter()
+        {
+            if is_out_of_time(timer, timeout, *nb_cube_expanded)
+            {
+                return false;
+            }
+            *nb_cube_expanded += 1;
+            let path = self.known_cubes[cube].clone();
+            for m in moves.iter()
+            {
+                // applies a move
+                let child_cube = cube.apply_move(m);
+                // saves the cube if it is new
+                if let Entry::Vacant(entry) = self.known_cubes.entry(child_cube.clone())
+                {
+                    let mut child_path = path.clone();
+                    child_path.push(m.description);
+                    child_path.shrink_to_fit();
+                    entry.insert(child_path);
+                    new_cubes.push(child_cube);
+                }
+            }
+        }
+        self.current_cubes = new_cubes;
+        self.current_distance += 1;
+        true
+    }
+}
+
+impl Cube
+{
+    /// solves the given cube with two breath first searches
+    /// one going forward from the cube and one going backward from the solved cubes
+    /// until they meet in the middle
+    /// NOTE:
+    /// - this algorithm has the particularity of not requiring an heuristic
+    /// - this algorithm *will* find an optimal solution
+    /// - each search only has to go half as deep as `solve_breath_first_search` which saves a lot of memory
+    ///
+    /// WARNING: this algorithm can still fill the available memory if one is not careful
+    ///
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_bidirectional_bfs(&self) -> Option<Vec<MoveDescription>>
+    {
+        self.solve_bidirectional_bfs_timed(Duration::MAX)
+    }
+
+    /// solves the given cube with two breath first searches meeting in the middle
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_bidirectional_bfs_timed(&self, timeout: Duration) -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.bidirectional_bfs(Metric::HalfTurn, timeout, &StdoutSink);
+        print_result(path, &stats, timeout, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube with two breath first searches meeting in the middle,
+    /// in the quarter turn metric (no 180° turns)
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH_QTM` moves (meaning that it is not solvable)
+    pub fn solve_bidirectional_bfs_qtm(&self) -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.bidirectional_bfs(Metric::QuarterTurn, Duration::MAX, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH_QTM)
+    }
+
+    /// solves the given cube with two breath first searches meeting in the middle
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_bidirectional_bfs_with_stats<S: ProgressSink>(&self,
+                                                               sink: &S)
+                                                               -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        self.bidirectional_bfs(Metric::HalfTurn, Duration::MAX, sink)
+    }
+
+    /// bidirectional breath first search
+    /// returns None if no solution was found in less than `timeout` or in less than `MAX_SOLUTION_LENGTH` moves
+    /// (as measured in the given metric)
+    fn bidirectional_bfs<S: ProgressSink>(&self,
+                                          metric: Metric,
+                                          timeout: Duration,
+                                          sink: &S)
+                                          -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
+        // used to time the computation
+        let timer = Stopwatch::start_new();
+        let mut nb_cube_expanded = 0;
+        // all moves that can be applied to a cube
+        let moves = Move::all_moves_with_metric(metric);
+        let max_length = max_solution_length(metric);
+
+        // the forward search starts from the cube
+        // the backward search starts from all solved cubes (in all orientations)
+        let mut forward = SearchSide::new(vec![self.clone()]);
+        let mut backward = SearchSide::new(Cube::solved_cubes().to_vec());
+        let mut meeting = forward.shortest_meeting(&backward, &forward.current_cubes);
+
+        // loop until both searches meet
+        while meeting.is_none()
+        {
+            // stops if the searches went deep enough to prove that there is no solution short enough
+            // (all paths up to the sum of the distances of both sides have been checked)
+            let max_depth_reached = forward.current_distance + backward.current_distance;
+            if max_depth_reached >= max_length
+            {
+                let stats = SolveStats { path_length: None,
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls: 0,
+                                         nb_transposition_hits: 0,
+                                         max_depth_reached,
+                                         elapsed: timer.elapsed() };
+                return (None, stats);
+            }
+
+            // expands the side with the smallest frontier
+            // we check all new cubes before stopping to insure that the path is the shortest
+            let is_forward = forward.current_cubes.len() <= backward.current_cubes.len();
+            let is_expanded = if is_forward
+            {
+                forward.expand(&moves, &mut nb_cube_expanded, &timer, timeout)
+            }
+            else
+            {
+                backward.expand(&moves, &mut nb_cube_expanded, &timer, timeout)
+            };
+
+            // stops if we ran out of time
+            if !is_expanded
+            {
+                let max_depth_reached = forward.current_distance + backward.current_distance;
+                let stats = SolveStats { path_length: None,
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls: 0,
+                                         nb_transposition_hits: 0,
+                                         max_depth_reached,
+                                         elapsed: timer.elapsed() };
+                return (None, stats);
+            }
+
+            // checks whether the new cubes are known by the other side
+            meeting = if is_forward
+            {
+                forward.shortest_meeting(&backward, &forward.current_cubes)
+            }
+            else
+            {
+                backward.shortest_meeting(&forward, &backward.current_cubes)
+                        .map(|(backward_path, forward_path)| (forward_path, backward_path))
+            };
+
+            // reports information on the run, the depth being the sum of the depths of both sides
+            let depth = forward.current_distance + backward.current_distance;
+            sink.report(ProgressEvent::DepthCompleted { solver: "Bidirectional Breath First Search",
+                                                        depth,
+                                                        nb_cube_expanded,
+                                                        nb_heuristic_calls: 0,
+                                                        elapsed: timer.elapsed() });
+        }
+
+        // the backward path goes from a solved cube to the meeting point, we invert it to go the other way
+        let (mut path, backward_path) = meeting.unwrap();
+        path.extend(invert_path(&backward_path));
+        let max_depth_reached = forward.current_distance + backward.current_distance;
+        let stats = SolveStats { path_length: Some(path.len()),
+                                 nb_cube_expanded,
+                                 nb_heuristic_calls: 0,
+                                 nb_transposition_hits: 0,
+                                 max_depth_reached,
+                                 elapsed: timer.elapsed() };
+        (Some(path), stats)
+    }
+}
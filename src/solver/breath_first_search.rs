@@ -1,76 +1,312 @@
-use std::collections::BTreeSet;
+use std::collections::{HashMap, hash_map::Entry};
+use std::time::Duration;
 use stopwatch::Stopwatch;
-use crate::cube::moves::{Move, MoveDescription};
-use crate::cube::Cube;
+use crate::cube::moves::{Move, MoveDescription, MoveTable};
+use crate::cube::{Cube, CubeIdentifier, Metric};
+use super::{already_solved, max_solution_length, MAX_SOLUTION_LENGTH, MAX_SOLUTION_LENGTH_QTM,
+            TIMEOUT_CHECK_PERIOD};
+use super::stats::{SolveStats, print_result};
+use super::cubeset::CubeSet;
+use super::{ProgressSink, ProgressEvent, StdoutSink};
+use rayon::prelude::*;
+
+/// number of cubes expanded in parallel before their children are deduplicated
+const CHUNK_SIZE: usize = 4096;
 
 impl Cube
 {
     /// solves the given cube by breath first search
     /// (which is equivalent to Diksjtra's algrithm here)
     /// NOTE: this algorithm has the particularity of not requiring an heuristic
+    /// NOTE: the cubes of each depth are expanded in parallel, the path returned is still deterministic
     /// WARNING: this algorithm can easily fill the available memory if one is not careful
-    pub fn solve_breath_first_search(&self) -> Vec<MoveDescription>
+    ///
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_breath_first_search(&self) -> Option<Vec<MoveDescription>>
+    {
+        self.solve_breath_first_search_timed(Duration::MAX)
+    }
+
+    /// solves the given cube by breath first search
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_breath_first_search_timed(&self, timeout: Duration) -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.breath_first_search(Metric::HalfTurn, timeout, &StdoutSink);
+        print_result(path, &stats, timeout, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube by breath first search, in the quarter turn metric (no 180° turns)
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH_QTM` moves (meaning that it is not solvable)
+    pub fn solve_breath_first_search_qtm(&self) -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.breath_first_search(Metric::QuarterTurn, Duration::MAX, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH_QTM)
+    }
+
+    /// solves the given cube by breath first search
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_breath_first_search_with_stats<S: ProgressSink>(&self,
+                                                                 sink: &S)
+                                                                 -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        self.breath_first_search(Metric::HalfTurn, Duration::MAX, sink)
+    }
+
+    /// returns the cubes that are exactly `depth` moves away from the cube (using all moves, in the half turn metric)
+    /// for example, all the positions 3 moves away from a solved cube
+    /// NOTE: slice moves can turn the cube as a whole, positions are thus compared up to a whole-cube rotation
+    /// (using `Cube::canonical`) and a single orientation of each position is returned, at its smallest depth
+    /// WARNING: this is done with a breath first search that keeps all the positions seen in memory
+    /// their number grows by a factor of about 20 per depth which fills the memory past a depth of 5 or 6
+    pub fn states_at_depth(&self, depth: usize) -> impl Iterator<Item = Cube>
+    {
+        let moves = Move::all_moves();
+        // all the positions seen so far, at the current depth or shallower, stored in canonical orientation
+        let mut known_cubes = CubeSet::unbounded();
+        known_cubes.insert(&self.canonical());
+        let mut cubes = vec![self.clone()];
+        for _depth in 0..depth
+        {
+            let mut children = Vec::new();
+            // expands the cubes in parallel, by chunks to bound the number of children kept in memory
+            for chunk in cubes.chunks(CHUNK_SIZE)
+            {
+                let new_children: Vec<(CubeIdentifier, Cube)> =
+                    chunk.par_iter()
+                         .flat_map_iter(|cube| moves.iter().map(move |m| cube.apply_move(m)))
+                         .map(|child| (child.canonical().to_identifier(), child))
+                         .filter(|(identifier, _child)| !known_cubes.contains_identifier(*identifier))
+                         .collect();
+                // deduplicates the children sequentially, keeping them in a deterministic order
+                for (identifier, child) in new_children
+                {
+                    if known_cubes.insert_identifier(identifier)
+                    {
+                        children.push(child);
+                    }
+                }
+            }
+            cubes = children;
+        }
+        cubes.into_iter()
+    }
+
+    /// returns the number of distinct positions at each distance from the solved cube, up to `max_depth` included
+    /// distances are measured with the face moves, in the half turn metric, and the centers stay in place
+    /// which matches the published counts (1, 18, 243, 3240, 43239, 574908, 7618438, ...)
+    /// NOTE: as the centers stay in place and all moves can be undone,
+    /// the children of a depth can only be at the previous, current or next depth
+    /// thus, only the cubes of the previous and current depth are kept to deduplicate the children
+    /// (this does not hold with slice moves: they turn the cube as a whole
+    /// and some orientations of a symmetric cube, such as the solved cube, share an identifier)
+    /// WARNING: the number of positions grows by a factor of about 13 per depth, filling the memory past a depth of 7
+    pub fn distance_distribution(max_depth: usize) -> Vec<usize>
+    {
+        let face_moves: Vec<Move> =
+            Move::all_moves().into_iter().filter(|m| !m.description.kind.is_center_layer()).collect();
+        let mut previous_depth = CubeSet::unbounded();
+        let mut current_depth = CubeSet::unbounded();
+        let solved_cube = Cube::solved();
+        current_depth.insert(&solved_cube);
+        let mut cubes = vec![solved_cube];
+        let mut distribution = vec![cubes.len()];
+        for _depth in 0..max_depth
+        {
+            let mut next_depth = CubeSet::unbounded();
+            let mut next_cubes = Vec::new();
+            // expands the cubes in parallel, by chunks to bound the number of children kept in memory
+            for chunk in cubes.chunks(CHUNK_SIZE)
+            {
+                let children: Vec<(CubeIdentifier, Cube)> =
+                    chunk.par_iter()
+                         .flat_map_iter(|cube| face_moves.iter().map(move |m| cube.apply_move(m)))
+                         .map(|child| (child.to_identifier(), child))
+                         .filter(|(identifier, _child)| !previous_depth.contains_identifier(*identifier))
+                         .filter(|(identifier, _child)| !current_depth.contains_identifier(*identifier))
+                         .collect();
+                // deduplicates the children sequentially
+                for (identifier, child) in children
+                {
+                    if next_depth.insert_identifier(identifier)
+                    {
+                        next_cubes.push(child);
+                    }
+                }
+            }
+            previous_depth = current_depth;
+            current_depth = next_depth;
+            cubes = next_cubes;
+            distribution.push(cubes.len());
+        }
+        distribution
+    }
+
+    /// breath first search
+    /// returns None if no solution was found in less than `timeout` or in less than `MAX_SOLUTION_LENGTH` moves
+    /// (as measured in the given metric)
+    fn breath_first_search<S: ProgressSink>(&self,
+                                            metric: Metric,
+                                            timeout: Duration,
+                                            sink: &S)
+                                            -> (Option<Vec<MoveDescription>>, SolveStats)
     {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
         // used to time the computation
         let timer = Stopwatch::start_new();
         let mut nb_cube_expanded = 0;
 
-        // all the cubes observed so far
-        let mut known_cubes = BTreeSet::new();
-        known_cubes.insert(self.to_identifier());
+        // all the cubes observed so far, with the move that led to them (None for the starting cube)
+        // storing a single move per cube, rather than a full path, keeps the memory use constant with depth
+        let mut known_cubes: HashMap<CubeIdentifier, Option<MoveDescription>> = HashMap::new();
+        known_cubes.insert(self.to_identifier(), None);
         // current distance and cubes at the current distance
         let mut current_distance = 0;
-        let mut current_cubes = vec![(self.clone(), Vec::new())];
+        let mut current_cubes = vec![self.clone()];
         // all moves that can be applied to a cube
-        let moves = Move::all_moves();
+        let moves = Move::all_moves_with_metric(metric);
+        let max_length = max_solution_length(metric);
 
         // loop until we reach a result
         loop
         {
             // checks all the cubes at the current depth
+            // chunk by chunk, to check the timeout regularly and bound the number of children kept in memory
             let mut new_cubes = Vec::new();
-            for (cube, path) in current_cubes
+            for cubes in current_cubes.chunks(TIMEOUT_CHECK_PERIOD)
             {
                 // checks if we reached the target
-                if cube.is_solved()
+                let solved_cube = cubes.iter().find(|cube| cube.is_solved());
+                // stops if we found a solution or ran out of time
+                if solved_cube.is_some() || (timer.elapsed() > timeout)
                 {
-                    println!("Done! Found a path of length {} in {:?} ({} cubes expanded / 0 heuristic call)",
-                    current_distance,
-                    timer.elapsed(),
-                    nb_cube_expanded);
-                    println!("Path: {:?}", path);
-                    return path;
+                    let path = solved_cube.map(|cube| path_to(cube, &known_cubes));
+                    let stats = SolveStats { path_length: solved_cube.map(|_| current_distance),
+                                             nb_cube_expanded,
+                                             nb_heuristic_calls: 0,
+                                             nb_transposition_hits: 0,
+                                             max_depth_reached: current_distance,
+                                             elapsed: timer.elapsed() };
+                    return (path, stats);
                 }
 
-                // expands the cube
-                nb_cube_expanded += 1;
-                for m in moves.iter()
+                // expands the cubes in parallel, keeping only the children that were not known before this chunk
+                nb_cube_expanded += cubes.len();
+                let children: Vec<(CubeIdentifier, Cube, MoveDescription)> =
+                    cubes.par_iter()
+                         .flat_map_iter(|cube| moves.iter().map(move |m| (cube.apply_move(m), m.description)))
+                         .map(|(child, description)| (child.to_identifier(), child, description))
+                         .filter(|(identifier, _child, _description)| !known_cubes.contains_key(identifier))
+                         .collect();
+                // saves the cubes, and the moves that produced them, sequentially
+                // as the children are kept in order, the first path found to a cube is the same as in a sequential search
+                for (identifier, child_cube, description) in children
                 {
-                    // applies a move
-                    let child_cube = cube.apply_move(m);
-                    // checks if the produced cube is new
-                    let is_new_cube = known_cubes.insert(child_cube.to_identifier());
-                    if is_new_cube
+                    if let Entry::Vacant(entry) = known_cubes.entry(identifier)
                     {
-                        // builds the path to the child cube
-                        let mut child_path = path.clone();
-                        child_path.push(m.description);
-                        child_path.shrink_to_fit();
-                        // saves the path to the child cube
-                        new_cubes.push((child_cube, child_path));
+                        entry.insert(Some(description));
+                        new_cubes.push(child_cube);
                     }
                 }
             }
 
-            // display information on the run
-            println!("Breath First Search: did distance {} in {:?} ({} cubes expanded / 0 heuristic call)",
-                     current_distance,
-                     timer.elapsed(),
-                     nb_cube_expanded);
+            // reports information on the run
+            sink.report(ProgressEvent::DepthCompleted { solver: "Breath First Search",
+                                                        depth: current_distance,
+                                                        nb_cube_expanded,
+                                                        nb_heuristic_calls: 0,
+                                                        elapsed: timer.elapsed() });
 
             // updates for the next iteration
             current_cubes = new_cubes;
             current_distance += 1;
+
+            // stops if there is no solution short enough
+            if current_distance > max_length
+            {
+                let stats = SolveStats { path_length: None,
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls: 0,
+                                         nb_transposition_hits: 0,
+                                         max_depth_reached: max_length,
+                                         elapsed: timer.elapsed() };
+                return (None, stats);
+            }
+        }
+    }
+}
+
+/// rebuilds the path leading to the given cube by undoing, one after the other, the moves that produced it
+/// NOTE: undoing a move gives back the exact parent cube, whose own move can then be found from its identifier
+fn path_to(cube: &Cube,
+           known_cubes: &HashMap<CubeIdentifier, Option<MoveDescription>>)
+           -> Vec<MoveDescription>
+{
+    let table = MoveTable::new();
+    let mut path = Vec::new();
+    let mut cube = cube.clone();
+    while let Some(m) = known_cubes[&cube.to_identifier()]
+    {
+        cube = cube.apply_move(table.get(&m.inverse()));
+        path.push(m);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::collections::HashSet;
+    use super::*;
+    use crate::solver::NullSink;
+
+    /// number of positions at each depth from a solved cube, with all moves and up to a whole-cube rotation
+    /// (a single slice move is a position of its own but, at depth 2, `R L'` is a rotation of `M` and is not counted)
+    #[test]
+    fn states_at_depth_counts()
+    {
+        let counts: Vec<usize> = (0..=2).map(|depth| Cube::solved().states_at_depth(depth).count()).collect();
+        assert_eq!(counts, vec![1, 27, 501]);
+    }
+
+    /// number of positions at each distance from the solved cube, in the half turn metric
+    #[test]
+    fn distance_distribution_counts()
+    {
+        assert_eq!(Cube::distance_distribution(3), vec![1, 18, 243, 3240]);
+    }
+
+    /// no position is returned twice, at the same depth or at two different depths
+    #[test]
+    fn states_at_depth_are_distinct()
+    {
+        let mut positions = HashSet::new();
+        for depth in 0..=2
+        {
+            for cube in Cube::solved().states_at_depth(depth)
+            {
+                assert!(positions.insert(cube.canonical().to_identifier()),
+                        "a position was found twice (at depth {})",
+                        depth);
+            }
+        }
+    }
+
+    /// the path rebuilt from the parent moves solves the cube and is never longer than the scramble
+    #[test]
+    fn path_solves_cube()
+    {
+        for seed in 0..5
+        {
+            let cube = Cube::solved().scramble_seeded(3, seed);
+            let (path, _) = cube.solve_breath_first_search_with_stats(&NullSink);
+            let path = path.unwrap();
+            assert!(path.len() <= 3);
+            assert!(cube.apply_path(&path).is_solved());
         }
     }
 }
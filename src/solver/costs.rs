@@ -0,0 +1,80 @@
+use enum_iterator::IntoEnumIterator;
+use crate::cube::moves::{MoveDescription, MoveKind, Amplitude};
+
+/// cost of each move, lets IDA* look for the cheapest solution instead of the shortest one
+/// (for example, by making the slice moves, which are harder to do with the fingers, more expensive)
+/// all moves cost 1 by default, making the cost of a path its length
+/// NOTE: costs are at least 1 so that a path can only get more expensive as it grows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveCosts
+{
+    /// cost of each move, indexed by kind then amplitude
+    costs: [[u8; Amplitude::VARIANT_COUNT]; MoveKind::VARIANT_COUNT]
+}
+
+impl MoveCosts
+{
+    /// all moves cost 1
+    pub fn uniform() -> MoveCosts
+    {
+        MoveCosts { costs: [[1; Amplitude::VARIANT_COUNT]; MoveKind::VARIANT_COUNT] }
+    }
+
+    /// sets the cost of the move of the given kind and amplitude
+    pub fn set_cost(&mut self, kind: MoveKind, amplitude: Amplitude, cost: u8)
+    {
+        assert!(cost >= 1, "set_cost: moves should cost at least 1");
+        self.costs[kind as usize][amplitude as usize] = cost;
+    }
+
+    /// sets the cost of all the moves of the given kind
+    pub fn set_kind_cost(&mut self, kind: MoveKind, cost: u8)
+    {
+        for amplitude in Amplitude::into_enum_iter()
+        {
+            self.set_cost(kind, amplitude, cost);
+        }
+    }
+
+    /// sets the cost of all the moves of the given amplitude
+    pub fn set_amplitude_cost(&mut self, amplitude: Amplitude, cost: u8)
+    {
+        for kind in MoveKind::into_enum_iter()
+        {
+            self.set_cost(kind, amplitude, cost);
+        }
+    }
+
+    /// returns the cost of the given move
+    pub fn cost(&self, m: MoveDescription) -> usize
+    {
+        self.costs[m.kind as usize][m.amplitude as usize] as usize
+    }
+
+    /// returns the sum of the costs of the moves of the path
+    pub fn path_cost(&self, path: &[MoveDescription]) -> usize
+    {
+        path.iter().map(|m| self.cost(*m)).sum()
+    }
+
+    /// returns the cost of the cheapest move
+    /// a heuristic, counting moves, times this cost is a lower bound on the cost of the moves left
+    pub fn min_cost(&self) -> usize
+    {
+        self.costs.iter().flatten().copied().min().unwrap_or(1) as usize
+    }
+
+    /// returns the cost of the most expensive move
+    pub fn max_cost(&self) -> usize
+    {
+        self.costs.iter().flatten().copied().max().unwrap_or(1) as usize
+    }
+}
+
+impl Default for MoveCosts
+{
+    fn default() -> Self
+    {
+        Self::uniform()
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{HashSet, VecDeque};
+use crate::cube::{Cube, CubeIdentifier};
+
+/// set of cubes, stored as identifiers, holding at most a given number of cubes
+/// once it is full, the oldest cubes are forgotten to make room for the new ones
+/// NOTE: identifiers do not rotate the faces, two orientations of a cube are thus usually two distinct cubes
+/// (insert `Cube::canonical` cubes to compare positions up to a whole-cube rotation)
+pub struct CubeSet
+{
+    /// a hash set has constant time insertions, which matters for the large sets built by breath first search
+    identifiers: HashSet<CubeIdentifier>,
+    /// identifiers in insertion order, used to evict the oldest cubes
+    /// (left empty if the set is unbounded)
+    insertion_order: VecDeque<CubeIdentifier>,
+    /// maximum number of cubes stored, `usize::MAX` if the set is unbounded
+    max_nb_cubes: usize
+}
+
+impl CubeSet
+{
+    /// creates an empty set that will hold at most `max_nb_cubes` cubes
+    pub fn new(max_nb_cubes: usize) -> CubeSet
+    {
+        CubeSet { identifiers: HashSet::new(), insertion_order: VecDeque::new(), max_nb_cubes }
+    }
+
+    /// creates an empty set that never forgets its cubes
+    /// WARNING: such a set can easily fill the available memory
+    pub fn unbounded() -> CubeSet
+    {
+        Self::new(usize::MAX)
+    }
+
+    /// returns true if the set never forgets its cubes
+    fn is_unbounded(&self) -> bool
+    {
+        self.max_nb_cubes == usize::MAX
+    }
+
+    /// returns true if the cube is in the set
+    pub fn contains(&self, cube: &Cube) -> bool
+    {
+        self.contains_identifier(cube.to_identifier())
+    }
+
+    /// returns true if the cube with the given identifier is in the set
+    pub fn contains_identifier(&self, identifier: CubeIdentifier) -> bool
+    {
+        self.identifiers.contains(&identifier)
+    }
+
+    /// adds a cube to the set, evicting the oldest cube if the set is full
+    /// returns false if the cube was already in the set
+    pub fn insert(&mut self, cube: &Cube) -> bool
+    {
+        self.insert_identifier(cube.to_identifier())
+    }
+
+    /// adds the cube with the given identifier to the set, evicting the oldest cube if the set is full
+    /// returns false if the cube was already in the set
+    pub fn insert_identifier(&mut self, identifier: CubeIdentifier) -> bool
+    {
+        if self.max_nb_cubes == 0
+        {
+            return false;
+        }
+        if !self.identifiers.insert(identifier)
+        {
+            return false;
+        }
+        if !self.is_unbounded()
+        {
+            self.insertion_order.push_back(identifier);
+            if self.insertion_order.len() > self.max_nb_cubes
+            {
+                let oldest = self.insertion_order.pop_front().unwrap();
+                self.identifiers.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// forgets all cubes
+    pub fn clear(&mut self)
+    {
+        self.identifiers.clear();
+        self.insertion_order.clear();
+    }
+
+    /// number of cubes in the set
+    pub fn len(&self) -> usize
+    {
+        self.identifiers.len()
+    }
+
+    /// returns true if the set contains no cube
+    pub fn is_empty(&self) -> bool
+    {
+        self.identifiers.is_empty()
+    }
+}
@@ -0,0 +1,158 @@
+//! Generation of labeled cubes, to train heuristics such as neural networks
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use rand::Rng;
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+use bincode::{serialize_into, deserialize_from};
+use crate::cube::{Cube, Color, NB_SQUARES_CUBE};
+use crate::solver::heuristic::{Heuristic, LoadError, SaveError, open_file};
+
+/// a scrambled cube labeled with its actual distance to a solved cube
+pub struct DistanceSample
+{
+    pub cube: Cube,
+    /// number of moves used to scramble the cube
+    pub scramble_length: usize,
+    /// length of an optimal solution, it can be smaller than the scramble length as moves can cancel out
+    pub distance: u8
+}
+
+/// format used to store the samples on disk, the cube is stored as the index of the color of each square
+#[derive(Serialize, Deserialize)]
+struct SampleRecord
+{
+    squares: Vec<u8>,
+    scramble_length: usize,
+    distance: u8
+}
+
+/// produces `nb_samples` cubes, scrambled with between 0 and `max_scramble` moves, labeled with their distance
+/// the samples are solved in parallel, using IDA* with the given heuristic
+/// WARNING: the time needed to solve a sample grows exponentially with the scramble length
+pub fn generate_distance_dataset<H: Heuristic + Sync>(nb_samples: usize,
+                                                      max_scramble: usize,
+                                                      heuristic: &H)
+                                                      -> Vec<DistanceSample>
+{
+    (0..nb_samples).into_par_iter()
+                   .map(|_| {
+                       let scramble_length = rand::thread_rng().gen_range(0..=max_scramble);
+                       let cube = Cube::scrambled(scramble_length);
+                       let distance = cube.optimal_distance(heuristic)
+                                          .expect("generate_distance_dataset: scrambled cubes are solvable");
+                       DistanceSample { cube, scramble_length, distance }
+                   })
+                   .collect()
+}
+
+/// saves the samples as a CSV file with one line per sample
+/// the columns are the colors of the squares (one letter per square, see `Cube::from_facelets`),
+/// the scramble length and the distance
+/// returns an error if the file cannot be written
+pub fn save_dataset_csv(samples: &[DistanceSample], file_name: &str) -> Result<(), SaveError>
+{
+    let unwritable = |error| SaveError::unwritable(file_name, error);
+    let mut file = BufWriter::new(File::create(file_name).map_err(unwritable)?);
+    writeln!(file, "cube,scramble_length,distance").map_err(unwritable)?;
+    for sample in samples
+    {
+        let facelets: String = sample.cube.squares.iter().map(|color| color.to_letter()).collect();
+        writeln!(file, "{},{},{}", facelets, sample.scramble_length, sample.distance).map_err(unwritable)?;
+    }
+    file.flush().map_err(unwritable)
+}
+
+/// saves the samples in a compact binary format, they can be loaded back with `load_dataset`
+/// returns an error if the file cannot be written
+pub fn save_dataset(samples: &[DistanceSample], file_name: &str) -> Result<(), SaveError>
+{
+    let records: Vec<SampleRecord> =
+        samples.iter()
+               .map(|sample| {
+                   let squares = sample.cube.squares.iter().map(|color| *color as u8).collect();
+                   let (scramble_length, distance) = (sample.scramble_length, sample.distance);
+                   SampleRecord { squares, scramble_length, distance }
+               })
+               .collect();
+    let file = File::create(file_name).map_err(|error| SaveError::unwritable(file_name, error))?;
+    let mut file = BufWriter::new(file);
+    serialize_into(&mut file, &records).map_err(|error| SaveError::from_bincode(file_name, *error))?;
+    file.flush().map_err(|error| SaveError::unwritable(file_name, error))
+}
+
+/// loads samples saved with `save_dataset`
+/// returns an error if the file cannot be read or if a sample does not describe a cube
+pub fn load_dataset(file_name: &str) -> Result<Vec<DistanceSample>, LoadError>
+{
+    let mut file = BufReader::new(open_file(file_name)?);
+    let records: Vec<SampleRecord> =
+        deserialize_from(&mut file).map_err(|error| LoadError::from_bincode(file_name, *error))?;
+    records.into_iter()
+           .enumerate()
+           .map(|(index, record)| {
+               let invalid_sample = |message: String| {
+                   LoadError::invalid_content(file_name, format!("sample {}: {}", index, message))
+               };
+               if record.squares.len() != NB_SQUARES_CUBE
+               {
+                   let message = format!("{} squares instead of {}", record.squares.len(), NB_SQUARES_CUBE);
+                   return Err(invalid_sample(message));
+               }
+               let mut cube = Cube::solved();
+               for (square, color) in cube.squares.iter_mut().zip(record.squares.iter())
+               {
+                   *square = Color::ALL.get(*color as usize)
+                                       .copied()
+                                       .ok_or_else(|| invalid_sample(format!("{} is not a color", color)))?;
+               }
+               Ok(DistanceSample { cube, scramble_length: record.scramble_length, distance: record.distance })
+           })
+           .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// saved samples are loaded back unchanged
+    #[test]
+    fn dataset_round_trip()
+    {
+        let file_name = std::env::temp_dir().join("deepcube_dataset_round_trip.bin");
+        let file_name = file_name.to_str().unwrap();
+        let samples: Vec<DistanceSample> =
+            (0..10).map(|seed| {
+                       let cube = Cube::solved().scramble_seeded(seed, seed as u64);
+                       DistanceSample { cube, scramble_length: seed, distance: seed as u8 }
+                   })
+                   .collect();
+        save_dataset(&samples, file_name).unwrap();
+        let loaded = load_dataset(file_name).unwrap();
+        std::fs::remove_file(file_name).unwrap();
+        assert_eq!(loaded.len(), samples.len());
+        for (sample, loaded) in samples.iter().zip(&loaded)
+        {
+            assert!(sample.cube == loaded.cube);
+            assert_eq!((sample.scramble_length, sample.distance), (loaded.scramble_length, loaded.distance));
+        }
+    }
+
+    /// records with a wrong number of squares or an unknown color are rejected
+    #[test]
+    fn invalid_records_are_rejected()
+    {
+        let file_name = std::env::temp_dir().join("deepcube_dataset_invalid.bin");
+        let file_name = file_name.to_str().unwrap();
+        let invalid_records = [vec![0; NB_SQUARES_CUBE - 1], vec![Color::ALL.len() as u8; NB_SQUARES_CUBE]];
+        for squares in invalid_records
+        {
+            let records = vec![SampleRecord { squares, scramble_length: 0, distance: 0 }];
+            serialize_into(BufWriter::new(File::create(file_name).unwrap()), &records).unwrap();
+            assert!(matches!(load_dataset(file_name), Err(LoadError::InvalidContent { .. })));
+        }
+        std::fs::remove_file(file_name).unwrap();
+        assert!(matches!(load_dataset(file_name), Err(LoadError::MissingFile { .. })));
+    }
+}
@@ -22,6 +22,11 @@ pub type AverageHeuristic = RawAverageHeuristic<false>;
 
 impl<const USE_RAW_SUM: bool> Heuristic for RawAverageHeuristic<USE_RAW_SUM>
 {
+    fn kind() -> String
+    {
+        format!("raw average (raw sum: {})", USE_RAW_SUM)
+    }
+
     /// returns a lower bound on the number of steps before the problem will be solved
     fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
     {
@@ -38,7 +43,7 @@ impl<const USE_RAW_SUM: bool> Heuristic for RawAverageHeuristic<USE_RAW_SUM>
         else
         {
             // ceil(average)
-            (sum_distances + 2) / 3
+            sum_distances.div_ceil(3)
         }
     }
 }
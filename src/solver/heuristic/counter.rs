@@ -1,11 +1,10 @@
 use serde::{Serialize, Deserialize, de::DeserializeOwned};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use crate::cube::Cube;
 use super::Heuristic;
 
 /// wrapper over heuristic to keep a count of the number of heuristic calls done
 #[derive(Serialize, Deserialize)]
-pub struct CounterHeuristic<H: Heuristic>
+pub struct CounterHeuristic<H>
 {
     /// how many times has the heuristic been called?
     count: AtomicUsize,
@@ -14,19 +13,41 @@ pub struct CounterHeuristic<H: Heuristic>
     pub heuristic: H
 }
 
-impl<H: Heuristic> Heuristic for CounterHeuristic<H>
+impl<C, H: Heuristic<C>> Heuristic<C> for CounterHeuristic<H>
 {
+    fn kind() -> String
+    {
+        format!("counter ({})", H::kind())
+    }
+
     /// returns a lower bound on the number of steps before the problem will be solved
-    fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
+    fn optimistic_distance_to_solved(&self, cube: &C) -> u8
     {
         // increases the count
         self.count.fetch_add(1, Ordering::Relaxed);
         // does the actual heuristic computation
         self.heuristic.optimistic_distance_to_solved(cube)
     }
+
+    /// counts one call per cube
+    fn optimistic_distance_to_solved_batch(&self, cubes: &[C]) -> Vec<u8>
+    {
+        self.count.fetch_add(cubes.len(), Ordering::Relaxed);
+        self.heuristic.optimistic_distance_to_solved_batch(cubes)
+    }
+
+    fn overestimation_factor(&self) -> f32
+    {
+        self.heuristic.overestimation_factor()
+    }
+
+    fn call_counter(&self) -> Option<&AtomicUsize>
+    {
+        Some(&self.count)
+    }
 }
 
-impl<H: Heuristic> CounterHeuristic<H>
+impl<H> CounterHeuristic<H>
 {
     /// initialize the heuristic
     pub fn new(heuristic: H) -> CounterHeuristic<H>
@@ -40,4 +61,10 @@ impl<H: Heuristic> CounterHeuristic<H>
     {
         self.count.load(Ordering::Relaxed)
     }
+
+    /// sets the number of calls back to zero
+    pub fn reset(&self)
+    {
+        self.count.store(0, Ordering::Relaxed)
+    }
 }
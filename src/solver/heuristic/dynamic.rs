@@ -0,0 +1,68 @@
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use super::Heuristic;
+use crate::cube::Cube;
+
+/// object safe version of `Heuristic`, implemented by all heuristics
+/// lets users pick a heuristic at runtime and pass it as a `&dyn DynHeuristic` to the `solve_*_dyn` functions
+/// C is the kind of cube the heuristic applies to, Rubik's cubes by default
+pub trait DynHeuristic<C = Cube>
+{
+    /// returns a lower bound on the number of move that will have to be applied before the problem will be solved
+    /// see `Heuristic::optimistic_distance_to_solved`
+    fn distance(&self, cube: &C) -> u8;
+
+    /// factor by which the heuristic might overestimate the distance to a solved cube
+    /// see `Heuristic::overestimation_factor`
+    fn max_overestimation(&self) -> f32;
+}
+
+impl<C, H: Heuristic<C>> DynHeuristic<C> for H
+{
+    fn distance(&self, cube: &C) -> u8
+    {
+        self.optimistic_distance_to_solved(cube)
+    }
+
+    fn max_overestimation(&self) -> f32
+    {
+        self.overestimation_factor()
+    }
+}
+
+/// wraps a `&dyn DynHeuristic` so that it can be given to the solvers
+/// it only exists for the duration of a solve and thus cannot be saved or loaded
+pub(crate) struct DynHeuristicRef<'a, C>(pub &'a dyn DynHeuristic<C>);
+
+impl<C> Heuristic<C> for DynHeuristicRef<'_, C>
+{
+    fn kind() -> String
+    {
+        "dynamic".to_string()
+    }
+
+    fn optimistic_distance_to_solved(&self, cube: &C) -> u8
+    {
+        self.0.distance(cube)
+    }
+
+    fn overestimation_factor(&self) -> f32
+    {
+        self.0.max_overestimation()
+    }
+}
+
+impl<C> Serialize for DynHeuristicRef<'_, C>
+{
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+    {
+        Err(serde::ser::Error::custom("cannot save a dynamic heuristic, save the wrapped heuristic instead"))
+    }
+}
+
+impl<'de, C> Deserialize<'de> for DynHeuristicRef<'_, C>
+{
+    fn deserialize<D: Deserializer<'de>>(_deserializer: D) -> Result<Self, D::Error>
+    {
+        Err(serde::de::Error::custom("cannot load a dynamic heuristic, load the wrapped heuristic instead"))
+    }
+}
@@ -0,0 +1,425 @@
+//! Saving and loading of precomputed tables
+//!
+//! files start with a header identifying their content, followed by the serialized heuristic (the payload)
+//! the header lets us reject, with a clear error, files that are truncated, corrupted,
+//! written by another version of the format or containing another heuristic
+//! (including one built with different parameters, such as `NB_MIDDLES_KEPT`)
+//! which would otherwise produce a heuristic that is not admissible
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use serde::{Serialize, de::DeserializeOwned};
+use bincode::{serialize_into, deserialize_from};
+use crc32fast::Hasher;
+
+/// bytes found at the beginning of all the files written by `save_into`
+const MAGIC: [u8; 8] = *b"deepcube";
+
+/// version of the file format, to be increased whenever the layout of the files changes
+/// (version 2 identifies the heuristics with explicit names instead of their Rust type names)
+const FORMAT_VERSION: u32 = 2;
+
+//-----------------------------------------------------------------------------
+// Errors
+
+/// error returned when a heuristic cannot be loaded from a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError
+{
+    /// there is no file with the given name (the tables might not have been generated yet)
+    MissingFile
+    {
+        file_name: String
+    },
+    /// the file exists but could not be read
+    Unreadable
+    {
+        file_name: String,
+        /// the underlying io error
+        message: String
+    },
+    /// the file was read but does not contain the expected heuristic
+    InvalidContent
+    {
+        file_name: String,
+        /// why the content could not be deserialized
+        message: String
+    },
+    /// the file does not start with the header written by `save`
+    NotATableFile
+    {
+        file_name: String
+    },
+    /// the file was written with another version of the file format
+    VersionMismatch
+    {
+        file_name: String,
+        found: u32,
+        expected: u32
+    },
+    /// the file contains another heuristic, or the same heuristic built with different parameters
+    KindMismatch
+    {
+        file_name: String,
+        found: String,
+        expected: String
+    },
+    /// the file is shorter or longer than stated in its header, or its checksum does not match its content
+    Corrupted
+    {
+        file_name: String
+    },
+    /// the file was loaded but could not be updated (such as a checkpoint while resuming the computation of a table)
+    Unwritable
+    {
+        file_name: String,
+        /// why the file could not be written
+        message: String
+    }
+}
+
+impl LoadError
+{
+    /// builds an error for a file whose content could not be deserialized
+    pub(crate) fn invalid_content<M: ToString>(file_name: &str, message: M) -> LoadError
+    {
+        LoadError::InvalidContent { file_name: file_name.to_string(), message: message.to_string() }
+    }
+
+    /// builds an error from a deserialization failure, reporting files that end too early as corrupted
+    pub(crate) fn from_bincode(file_name: &str, error: bincode::ErrorKind) -> LoadError
+    {
+        match error
+        {
+            bincode::ErrorKind::Io(error) if error.kind() == ErrorKind::UnexpectedEof =>
+            {
+                LoadError::Corrupted { file_name: file_name.to_string() }
+            }
+            error => LoadError::invalid_content(file_name, error)
+        }
+    }
+
+    /// builds an error for a file that was loaded but could not be written back
+    pub(crate) fn from_save_error(error: SaveError) -> LoadError
+    {
+        match error
+        {
+            SaveError::Unwritable { file_name, message } | SaveError::Unserializable { file_name, message } =>
+            {
+                LoadError::Unwritable { file_name, message }
+            }
+        }
+    }
+
+    /// builds an error for a file that could not be opened or read
+    pub(crate) fn unreadable(file_name: &str, error: std::io::Error) -> LoadError
+    {
+        match error.kind()
+        {
+            ErrorKind::NotFound => LoadError::MissingFile { file_name: file_name.to_string() },
+            _ => LoadError::Unreadable { file_name: file_name.to_string(), message: error.to_string() }
+        }
+    }
+}
+
+impl std::fmt::Display for LoadError
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self
+        {
+            LoadError::MissingFile { file_name } =>
+            {
+                write!(formater, "the file '{}' does not exist", file_name)
+            }
+            LoadError::Unreadable { file_name, message } =>
+            {
+                write!(formater, "unable to read the file '{}': {}", file_name, message)
+            }
+            LoadError::InvalidContent { file_name, message } =>
+            {
+                write!(formater,
+                       "the file '{}' does not contain the expected heuristic: {}",
+                       file_name,
+                       message)
+            }
+            LoadError::NotATableFile { file_name } =>
+            {
+                write!(formater, "the file '{}' was not written by `save` (it has no header)", file_name)
+            }
+            LoadError::VersionMismatch { file_name, found, expected } =>
+            {
+                write!(formater,
+                       "the file '{}' uses version {} of the file format but version {} is expected, \
+                        it should be regenerated",
+                       file_name,
+                       found,
+                       expected)
+            }
+            LoadError::KindMismatch { file_name, found, expected } =>
+            {
+                write!(formater,
+                       "the file '{}' contains a '{}' but a '{}' is expected, \
+                        it might have been built with different parameters",
+                       file_name,
+                       found,
+                       expected)
+            }
+            LoadError::Corrupted { file_name } =>
+            {
+                write!(formater, "the file '{}' is truncated or corrupted, regenerate it", file_name)
+            }
+            LoadError::Unwritable { file_name, message } =>
+            {
+                write!(formater, "unable to update the file '{}': {}", file_name, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// error returned when a heuristic cannot be saved to a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SaveError
+{
+    /// the file could not be created or written
+    Unwritable
+    {
+        file_name: String,
+        /// the underlying io error
+        message: String
+    },
+    /// the heuristic could not be serialized (this is the case of heuristics chosen at runtime)
+    Unserializable
+    {
+        file_name: String,
+        /// why the heuristic could not be serialized
+        message: String
+    }
+}
+
+impl SaveError
+{
+    /// builds an error for a file that could not be created or written
+    pub(crate) fn unwritable(file_name: &str, error: std::io::Error) -> SaveError
+    {
+        SaveError::Unwritable { file_name: file_name.to_string(), message: error.to_string() }
+    }
+
+    /// builds an error from a serialization failure, distinguishing io errors from serialization errors
+    pub(crate) fn from_bincode(file_name: &str, error: bincode::ErrorKind) -> SaveError
+    {
+        match error
+        {
+            bincode::ErrorKind::Io(error) => SaveError::unwritable(file_name, error),
+            error =>
+            {
+                SaveError::Unserializable { file_name: file_name.to_string(), message: error.to_string() }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SaveError
+{
+    fn fmt(&self, formater: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self
+        {
+            SaveError::Unwritable { file_name, message } =>
+            {
+                write!(formater, "unable to write the file '{}': {}", file_name, message)
+            }
+            SaveError::Unserializable { file_name, message } =>
+            {
+                write!(formater, "unable to serialize the heuristic into '{}': {}", file_name, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+//-----------------------------------------------------------------------------
+// Checksums
+
+/// writer computing the checksum, and counting the bytes, of everything written through it
+struct ChecksumWriter<W>
+{
+    writer: W,
+    hasher: Hasher,
+    length: u64
+}
+
+impl<W: Write> Write for ChecksumWriter<W>
+{
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize>
+    {
+        let nb_written = self.writer.write(buffer)?;
+        self.hasher.update(&buffer[..nb_written]);
+        self.length += nb_written as u64;
+        Ok(nb_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()>
+    {
+        self.writer.flush()
+    }
+}
+
+/// reader computing the checksum, and counting the bytes, of everything read through it
+struct ChecksumReader<R>
+{
+    reader: R,
+    hasher: Hasher,
+    length: u64
+}
+
+impl<R: Read> Read for ChecksumReader<R>
+{
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize>
+    {
+        let nb_read = self.reader.read(buffer)?;
+        self.hasher.update(&buffer[..nb_read]);
+        self.length += nb_read as u64;
+        Ok(nb_read)
+    }
+}
+
+/// computes the length and checksum of the serialized value without storing it
+/// NOTE: the checksum is computed on buffered chunks as the serializer writes tables one byte at a time
+fn length_and_checksum<T: Serialize>(value: &T) -> bincode::Result<(u64, u32)>
+{
+    let writer = ChecksumWriter { writer: io::sink(), hasher: Hasher::new(), length: 0 };
+    let mut writer = BufWriter::new(writer);
+    serialize_into(&mut writer, value)?;
+    let writer = writer.into_inner().map_err(|error| error.into_error())?;
+    Ok((writer.length, writer.hasher.finalize()))
+}
+
+//-----------------------------------------------------------------------------
+// Saving and loading
+
+/// serializes the given value into the given file, after a header describing it
+/// NOTE: the file is flushed explicitly so that a failure to write its end is reported
+/// `kind` identifies the content of the file, it is checked when loading it
+pub(crate) fn save_into<T: Serialize>(value: &T, kind: &str, file_name: &str) -> Result<(), SaveError>
+{
+    // the header needs the length and checksum of the payload which are computed in a first pass
+    let (payload_length, checksum) =
+        length_and_checksum(value).map_err(|error| SaveError::from_bincode(file_name, *error))?;
+    let file = File::create(file_name).map_err(|error| SaveError::unwritable(file_name, error))?;
+    let mut file = BufWriter::new(file);
+    let header = (MAGIC, FORMAT_VERSION, kind, payload_length, checksum);
+    serialize_into(&mut file, &header).map_err(|error| SaveError::from_bincode(file_name, *error))?;
+    serialize_into(&mut file, value).map_err(|error| SaveError::from_bincode(file_name, *error))?;
+    file.flush().map_err(|error| SaveError::unwritable(file_name, error))
+}
+
+/// reads the header of a file, checking that it contains the given kind of value
+/// returns the length and checksum of the payload that follows
+fn read_header<R: Read>(reader: &mut R, kind: &str, file_name: &str) -> Result<(u64, u32), LoadError>
+{
+    // the magic bytes are checked first so that we do not interpret random bytes as a header
+    let not_a_table_file = LoadError::NotATableFile { file_name: file_name.to_string() };
+    match deserialize_from::<_, [u8; 8]>(&mut *reader)
+    {
+        Ok(magic) if magic == MAGIC => (),
+        _ => return Err(not_a_table_file)
+    }
+    let from_bincode = |error: bincode::Error| LoadError::from_bincode(file_name, *error);
+    let version: u32 = deserialize_from(&mut *reader).map_err(from_bincode)?;
+    if version != FORMAT_VERSION
+    {
+        return Err(LoadError::VersionMismatch { file_name: file_name.to_string(),
+                                                found: version,
+                                                expected: FORMAT_VERSION });
+    }
+    let found_kind: String = deserialize_from(&mut *reader).map_err(from_bincode)?;
+    if found_kind != kind
+    {
+        return Err(LoadError::KindMismatch { file_name: file_name.to_string(),
+                                             found: found_kind,
+                                             expected: kind.to_string() });
+    }
+    let payload_length: u64 = deserialize_from(&mut *reader).map_err(from_bincode)?;
+    let checksum: u32 = deserialize_from(&mut *reader).map_err(from_bincode)?;
+    Ok((payload_length, checksum))
+}
+
+/// loads a value written by `save_into` from the given file
+/// checking its header (which should contain the given kind) as well as the length and checksum of its payload
+pub(crate) fn load_from<T: DeserializeOwned>(kind: &str, file_name: &str) -> Result<T, LoadError>
+{
+    let mut file = open_file(file_name)?;
+    let (payload_length, checksum) = read_header(&mut file, kind, file_name)?;
+    // the checksum is computed below the buffer so that it is updated by large chunks
+    let reader = ChecksumReader { reader: file, hasher: Hasher::new(), length: 0 };
+    let mut reader = BufReader::new(reader);
+    let value = deserialize_from(&mut reader).map_err(|error| LoadError::from_bincode(file_name, *error))?;
+    // reads any trailing bytes so that they are included in the length and checksum
+    io::copy(&mut reader, &mut io::sink()).map_err(|error| LoadError::unreadable(file_name, error))?;
+    let reader = reader.into_inner();
+    if (reader.length != payload_length) || (reader.hasher.finalize() != checksum)
+    {
+        return Err(LoadError::Corrupted { file_name: file_name.to_string() });
+    }
+    Ok(value)
+}
+
+/// checks the header of a memory-mapped file written by `save_into`
+/// returns the offset at which the payload starts
+/// NOTE: the checksum is not verified as it would require reading the whole file, defeating memory-mapping,
+/// but the length of the file is checked which detects truncated files
+pub(crate) fn check_mapped_header(bytes: &[u8], kind: &str, file_name: &str) -> Result<usize, LoadError>
+{
+    let mut reader = bytes;
+    let (payload_length, _checksum) = read_header(&mut reader, kind, file_name)?;
+    if (reader.len() as u64) != payload_length
+    {
+        return Err(LoadError::Corrupted { file_name: file_name.to_string() });
+    }
+    Ok(bytes.len() - reader.len())
+}
+
+/// opens the given file, turning a failure into a `LoadError`
+pub(crate) fn open_file(file_name: &str) -> Result<File, LoadError>
+{
+    File::open(file_name).map_err(|error| LoadError::unreadable(file_name, error))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::solver::heuristic::{Heuristic, StickerHeuristic, CornerPermutationHeuristic, MiddleHeuristic,
+                                   LowerMiddleHeuristic, UpperMiddleHeuristic};
+
+    /// heuristics differing by their parameters do not share a kind
+    #[test]
+    fn kinds_are_distinct()
+    {
+        let kinds = [StickerHeuristic::kind(),
+                     CornerPermutationHeuristic::kind(),
+                     LowerMiddleHeuristic::kind(),
+                     UpperMiddleHeuristic::kind(),
+                     MiddleHeuristic::<7, true>::kind()];
+        for (i, kind) in kinds.iter().enumerate()
+        {
+            assert!(kinds[..i].iter().all(|other| other != kind), "'{}' is used twice", kind);
+        }
+    }
+
+    /// a file holding another kind of heuristic is rejected
+    #[test]
+    fn kind_mismatch()
+    {
+        let file_name = std::env::temp_dir().join("deepcube_kind_mismatch.bin");
+        let file_name = file_name.to_str().unwrap();
+        StickerHeuristic.save(file_name).unwrap();
+        let error = CornerPermutationHeuristic::load(file_name).err().unwrap();
+        assert!(matches!(error, LoadError::KindMismatch { .. }));
+        assert!(StickerHeuristic::load(file_name).is_ok());
+        std::fs::remove_file(file_name).unwrap();
+    }
+}
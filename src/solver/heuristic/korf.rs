@@ -1,6 +1,10 @@
 use serde::{Serialize, Deserialize};
 use super::{Heuristic, UpperMiddleHeuristic, LowerMiddleHeuristic, CornerHeuristic};
+use super::{PackedUpperMiddleHeuristic, PackedLowerMiddleHeuristic, PackedCornerHeuristic};
+use super::table::map_file;
+use super::{LoadError, check_mapped_header};
 use crate::cube::Cube;
+use crate::solver::{ProgressSink, StdoutSink};
 
 /// maximum between the corners heuristic and the middles heuristic
 #[derive(Serialize, Deserialize)]
@@ -13,6 +17,11 @@ pub struct KorfHeuristic
 
 impl Heuristic for KorfHeuristic
 {
+    fn kind() -> String
+    {
+        "korf".to_string()
+    }
+
     /// returns a lower bound on the number of steps before the problem will be solved
     fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
     {
@@ -28,9 +37,105 @@ impl KorfHeuristic
     /// initialize the heuristic
     pub fn new() -> KorfHeuristic
     {
-        let corners_heuristic = CornerHeuristic::new();
-        let lower_middles_heuristic = LowerMiddleHeuristic::new();
-        let upper_middles_heuristic = UpperMiddleHeuristic::new();
+        Self::new_with_sink(&StdoutSink)
+    }
+
+    /// initialize the heuristic, reporting progress to the given sink
+    pub fn new_with_sink<S: ProgressSink>(sink: &S) -> KorfHeuristic
+    {
+        let corners_heuristic = CornerHeuristic::new_with_sink(sink);
+        let lower_middles_heuristic = LowerMiddleHeuristic::new_with_sink(sink);
+        let upper_middles_heuristic = UpperMiddleHeuristic::new_with_sink(sink);
+        KorfHeuristic { corners_heuristic, lower_middles_heuristic, upper_middles_heuristic }
+    }
+
+    /// initialize the heuristic in the quarter turn metric (no 180° turns)
+    /// WARNING: the resulting heuristic is only admissible for solvers using the quarter turn metric
+    pub fn new_qtm() -> KorfHeuristic
+    {
+        Self::new_qtm_with_sink(&StdoutSink)
+    }
+
+    /// initialize the heuristic in the quarter turn metric, reporting progress to the given sink
+    pub fn new_qtm_with_sink<S: ProgressSink>(sink: &S) -> KorfHeuristic
+    {
+        let corners_heuristic = CornerHeuristic::new_qtm_with_sink(sink);
+        let lower_middles_heuristic = LowerMiddleHeuristic::new_qtm_with_sink(sink);
+        let upper_middles_heuristic = UpperMiddleHeuristic::new_qtm_with_sink(sink);
         KorfHeuristic { corners_heuristic, lower_middles_heuristic, upper_middles_heuristic }
     }
+
+    /// loads the heuristic from the given file, memory-mapping the tables instead of reading them
+    /// see `TableHeuristic::load_mmap`
+    pub fn load_mmap(file_name: &str) -> Result<KorfHeuristic, LoadError>
+    {
+        let file = map_file(file_name)?;
+        let mut offset = check_mapped_header(&file, &Self::kind(), file_name)?;
+        let corners_heuristic = CornerHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        let lower_middles_heuristic = LowerMiddleHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        let upper_middles_heuristic = UpperMiddleHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        Ok(KorfHeuristic { corners_heuristic, lower_middles_heuristic, upper_middles_heuristic })
+    }
+
+    /// returns the largest distance stored in the tables
+    /// see `TableHeuristic::max_distance`
+    pub fn max_distance(&self) -> u8
+    {
+        self.corners_heuristic
+            .max_distance()
+            .max(self.lower_middles_heuristic.max_distance())
+            .max(self.upper_middles_heuristic.max_distance())
+    }
+
+    /// returns the number of bytes used by the tables and their encoders
+    /// see `TableHeuristic::memory_bytes`
+    pub fn memory_bytes(&self) -> usize
+    {
+        let corners_bytes = self.corners_heuristic.memory_bytes();
+        let lower_middles_bytes = self.lower_middles_heuristic.memory_bytes();
+        let upper_middles_bytes = self.upper_middles_heuristic.memory_bytes();
+        corners_bytes + lower_middles_bytes + upper_middles_bytes
+    }
+
+    /// converts the heuristic into a packed heuristic that uses half the memory
+    pub fn pack(self) -> PackedKorfHeuristic
+    {
+        PackedKorfHeuristic { corners_heuristic: self.corners_heuristic.pack(),
+                              lower_middles_heuristic: self.lower_middles_heuristic.pack(),
+                              upper_middles_heuristic: self.upper_middles_heuristic.pack() }
+    }
+}
+
+impl Default for KorfHeuristic
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+/// korf heuristic storing two distances per byte
+#[derive(Serialize, Deserialize)]
+pub struct PackedKorfHeuristic
+{
+    pub corners_heuristic: PackedCornerHeuristic,
+    pub lower_middles_heuristic: PackedLowerMiddleHeuristic,
+    pub upper_middles_heuristic: PackedUpperMiddleHeuristic
+}
+
+impl Heuristic for PackedKorfHeuristic
+{
+    fn kind() -> String
+    {
+        "packed korf".to_string()
+    }
+
+    /// returns a lower bound on the number of steps before the problem will be solved
+    fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
+    {
+        let corners_distance = self.corners_heuristic.optimistic_distance_to_solved(cube);
+        let lower_middles_distance = self.lower_middles_heuristic.optimistic_distance_to_solved(cube);
+        let upper_middles_distance = self.upper_middles_heuristic.optimistic_distance_to_solved(cube);
+        corners_distance.max(lower_middles_distance).max(upper_middles_distance)
+    }
 }
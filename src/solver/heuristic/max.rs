@@ -0,0 +1,58 @@
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use std::sync::atomic::AtomicUsize;
+use super::Heuristic;
+
+/// maximum of two heuristics
+/// the maximum of admissible heuristics is admissible
+/// more than two heuristics can be combined by nesting `MaxHeuristic` (see `Heuristic::max_with`)
+#[derive(Serialize, Deserialize)]
+pub struct MaxHeuristic<H1, H2>
+{
+    #[serde(bound(deserialize = "H1: DeserializeOwned"))]
+    pub first_heuristic: H1,
+    #[serde(bound(deserialize = "H2: DeserializeOwned"))]
+    pub second_heuristic: H2
+}
+
+impl<C, H1: Heuristic<C>, H2: Heuristic<C>> Heuristic<C> for MaxHeuristic<H1, H2>
+{
+    fn kind() -> String
+    {
+        format!("max ({}, {})", H1::kind(), H2::kind())
+    }
+
+    /// returns a lower bound on the number of steps before the problem will be solved
+    fn optimistic_distance_to_solved(&self, cube: &C) -> u8
+    {
+        let first_distance = self.first_heuristic.optimistic_distance_to_solved(cube);
+        let second_distance = self.second_heuristic.optimistic_distance_to_solved(cube);
+        first_distance.max(second_distance)
+    }
+
+    fn optimistic_distance_to_solved_batch(&self, cubes: &[C]) -> Vec<u8>
+    {
+        let first_distances = self.first_heuristic.optimistic_distance_to_solved_batch(cubes);
+        let second_distances = self.second_heuristic.optimistic_distance_to_solved_batch(cubes);
+        first_distances.into_iter().zip(second_distances).map(|(first, second)| first.max(second)).collect()
+    }
+
+    /// the distances might be overestimated as much as the worst of the two heuristics
+    fn overestimation_factor(&self) -> f32
+    {
+        self.first_heuristic.overestimation_factor().max(self.second_heuristic.overestimation_factor())
+    }
+
+    fn call_counter(&self) -> Option<&AtomicUsize>
+    {
+        self.first_heuristic.call_counter().or_else(|| self.second_heuristic.call_counter())
+    }
+}
+
+impl<H1, H2> MaxHeuristic<H1, H2>
+{
+    /// initialize the heuristic
+    pub fn new(first_heuristic: H1, second_heuristic: H2) -> MaxHeuristic<H1, H2>
+    {
+        MaxHeuristic { first_heuristic, second_heuristic }
+    }
+}
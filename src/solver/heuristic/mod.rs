@@ -1,37 +1,86 @@
 use crate::cube::Cube;
 // heuristics
 mod table;
-pub use table::{CornerHeuristic, LowerMiddleHeuristic, UpperMiddleHeuristic};
+pub use table::{CornerHeuristic, InterleavedCornerHeuristic, CornerPermutationHeuristic, MiddleHeuristic,
+                LowerMiddleHeuristic, UpperMiddleHeuristic, EdgeOrientationHeuristic,
+                CornerOrientationHeuristic, DEFAULT_NB_MIDDLES_KEPT};
+pub use table::{PackedTableHeuristic, PackedCornerHeuristic, PackedMiddleHeuristic,
+                PackedLowerMiddleHeuristic, PackedUpperMiddleHeuristic};
+pub use table::{SymmetricTableHeuristic, SymmetricCornerHeuristic, MoveSet};
+pub use table::{PhaseOneCornerHeuristic, PhaseOneMiddleHeuristic, PhaseTwoCornerHeuristic,
+                PhaseTwoMiddleHeuristic};
 mod korf;
-pub use korf::KorfHeuristic;
+pub use korf::{KorfHeuristic, PackedKorfHeuristic};
+mod two_phase;
+pub use two_phase::{TwoPhaseTables, is_phase_two_move};
 mod average;
 pub use average::{AverageHeuristic, SumHeuristic};
 mod counter;
 pub use counter::CounterHeuristic;
+mod scaled;
+pub use scaled::ScaledHeuristic;
+mod max;
+pub use max::MaxHeuristic;
+mod pocket;
+pub use pocket::PocketHeuristic;
+mod sticker;
+pub use sticker::StickerHeuristic;
+mod dynamic;
+pub use dynamic::DynHeuristic;
+pub(crate) use dynamic::DynHeuristicRef;
+mod files;
+pub use files::{LoadError, SaveError};
+pub(crate) use files::{open_file, save_into, load_from, check_mapped_header};
+/// number of random moves used to produce the random cubes sampled by `max_optimal_depth`
+const SAMPLE_SCRAMBLE_LENGTH: usize = 100;
 // for serialization
-use std::fs::File;
-use std::io::{BufWriter, BufReader};
+use std::sync::atomic::AtomicUsize;
 use serde::{Serialize, de::DeserializeOwned};
-use bincode::{serialize_into, deserialize_from};
 
 /// implemented by all heuristics to be used in algorithms such as A*
-pub trait Heuristic: Serialize + DeserializeOwned + Sized
+/// C is the kind of cube the heuristic applies to, Rubik's cubes by default
+pub trait Heuristic<C = Cube>: Serialize + DeserializeOwned + Sized
 {
     /// returns a lower bound on the number of move that will have to be applied before the problem will be solved
-    fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8;
+    fn optimistic_distance_to_solved(&self, cube: &C) -> u8;
+
+    /// returns a lower bound for each of the given cubes
+    /// heuristics that can amortize their work over several cubes (such as a neural network) should override it
+    fn optimistic_distance_to_solved_batch(&self, cubes: &[C]) -> Vec<u8>
+    {
+        cubes.iter().map(|cube| self.optimistic_distance_to_solved(cube)).collect()
+    }
+
+    /// factor by which the heuristic might overestimate the distance to a solved cube
+    /// 1 for admissible heuristics, solvers use it to know how deep they have to search before giving up
+    fn overestimation_factor(&self) -> f32
+    {
+        1.0
+    }
+
+    /// counter of the number of calls to the heuristic, if it is wrapped in a `CounterHeuristic`
+    /// this lets us read the number of calls once the heuristic has been passed to a solver
+    fn call_counter(&self) -> Option<&AtomicUsize>
+    {
+        None
+    }
+
+    /// identifies the heuristic, and any parameter changing its distances, in the header of the files it is saved into
+    /// loading a file saved by a heuristic of another kind fails with `LoadError::KindMismatch`
+    fn kind() -> String;
 
     /// save the heuristic to the given file
-    fn save(&self, file_name: &str)
+    /// returns an error if the file cannot be written
+    fn save(&self, file_name: &str) -> Result<(), SaveError>
     {
-        let mut file = BufWriter::new(File::create(file_name).expect("save: unable to create the file"));
-        serialize_into(&mut file, self).expect("save: unable to serialize");
+        save_into(self, &Self::kind(), file_name)
     }
 
     /// loads the heuristic from the given file
-    fn load(file_name: &str) -> Self
+    /// returns an error if the file is missing, corrupted or does not contain a heuristic of this type
+    fn load(file_name: &str) -> Result<Self, LoadError>
     {
-        let mut file = BufReader::new(File::open(file_name).expect("load: unable to create the file"));
-        deserialize_from(&mut file).expect("load: unable to deserialize")
+        load_from(&Self::kind(), file_name)
     }
 
     /// wraps the heuristic with a counter so that we can keep track of the number of heuristic calls
@@ -39,4 +88,33 @@ pub trait Heuristic: Serialize + DeserializeOwned + Sized
     {
         CounterHeuristic::new(self)
     }
+
+    /// combines the heuristic with another one by taking the maximum of their distances
+    /// see `MaxHeuristic`
+    fn max_with<H: Heuristic<C>>(self, other: H) -> MaxHeuristic<Self, H>
+    {
+        MaxHeuristic::new(self, other)
+    }
+
+    /// multiplies the distances of the heuristic by the given weight to trade optimality for speed
+    /// see `ScaledHeuristic`
+    fn scaled(self, weight: f32) -> ScaledHeuristic<Self>
+    {
+        ScaledHeuristic::new(self, weight)
+    }
+}
+
+impl Cube
+{
+    /// returns the largest distance given by the heuristic on `nb_samples` random cubes
+    /// for an admissible heuristic, this is a lower bound on God's number (the largest distance to a solved cube)
+    /// it should thus never be above `MAX_SOLUTION_LENGTH`, which would mean that the heuristic is not admissible
+    /// see `TableHeuristic::max_distance` for an exhaustive check of table heuristics
+    pub fn max_optimal_depth<H: Heuristic>(heuristic: &H, nb_samples: usize) -> u8
+    {
+        (0..nb_samples).map(|_| Cube::scrambled(SAMPLE_SCRAMBLE_LENGTH))
+                       .map(|cube| heuristic.optimistic_distance_to_solved(&cube))
+                       .max()
+                       .unwrap_or(0)
+    }
 }
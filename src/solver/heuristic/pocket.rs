@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+use enum_iterator::IntoEnumIterator;
+use serde::{Serialize, Deserialize};
+use std::sync::atomic::AtomicUsize;
+use super::Heuristic;
+use crate::cube::{Cube, PocketCube, Color, Coordinate1D, RotationAxis, NB_SQUARES_POCKET_CUBE};
+use crate::cube::coordinates::Coordinate3D;
+use crate::cube::sizes::NB_SQUARES_SIDE_POCKET;
+use crate::cube::pocket::to_corner_square;
+
+/// a way to put a pocket cube on the corners of a Rubik's cube
+struct Orientation
+{
+    /// for each square of the pocket cube, the index of the corresponding corner square of the Rubik's cube
+    placement: [usize; NB_SQUARES_POCKET_CUBE],
+    /// squares of the pocket cube (and their expected color) that end up on the down-back-left corner
+    /// the orientation is the right one if the corner is in place
+    fixed_corner: [(usize, Color); 3]
+}
+
+/// computes all 24 orientations of the pocket cube
+/// done by rotating the squares until all possibilities are reached
+fn all_orientations() -> Vec<Orientation>
+{
+    // all permutations of the squares obtained by rotating the cube
+    let mut permutations = vec![std::array::from_fn::<usize, NB_SQUARES_POCKET_CUBE, _>(|index| index)];
+    let mut result = BTreeSet::new();
+    while !permutations.is_empty()
+    {
+        let mut new_permutations = Vec::new();
+        for permutation in permutations
+        {
+            let is_new = result.insert(permutation);
+            if is_new
+            {
+                for axis in RotationAxis::into_enum_iter()
+                {
+                    let rotate = |x: usize| Coordinate1D::<NB_SQUARES_SIDE_POCKET> { x }.rotate(axis).x;
+                    new_permutations.push(permutation.map(rotate));
+                }
+            }
+        }
+        permutations = new_permutations;
+    }
+
+    // squares of the down-back-left corner of the Rubik's cube and their color once solved
+    let solved_cube = Cube::solved();
+    let corner_squares = [RotationAxis::LeftRight, RotationAxis::DownUp, RotationAxis::FrontBack].map(|axis| {
+                             let index = Coordinate3D::new(0, 0, 2, axis).to_1D().x;
+                             (index, solved_cube.squares[index])
+                         });
+
+    // converts the permutations into orientations
+    result.into_iter()
+          .map(|permutation| {
+              let placement = permutation.map(to_corner_square);
+              let fixed_corner = corner_squares.map(|(corner_index, color)| {
+                                                   let index = placement.iter()
+                                                                        .position(|i| *i == corner_index)
+                                                                        .unwrap();
+                                                   (index, color)
+                                               });
+              Orientation { placement, fixed_corner }
+          })
+          .collect()
+}
+
+/// uses an heuristic on the corners of the Rubik's cube to solve the pocket cube
+/// the pocket cube is rotated so that its down-back-left corner is in place
+/// (it can then be solved without moving that corner, which is what a corner heuristic assumes)
+/// and its squares are used as the corners of a Rubik's cube
+/// WARNING: this is only admissible if the wrapped heuristic only looks at the corners of the cube
+#[derive(Serialize, Deserialize)]
+pub struct PocketHeuristic<H>
+{
+    pub corners_heuristic: H,
+    /// precomputed orientations, there is no need to save them
+    #[serde(skip, default = "all_orientations")]
+    orientations: Vec<Orientation>
+}
+
+impl<H: Heuristic> Heuristic<PocketCube> for PocketHeuristic<H>
+{
+    fn kind() -> String
+    {
+        format!("pocket ({})", H::kind())
+    }
+
+    /// returns a lower bound on the number of steps before the problem will be solved
+    fn optimistic_distance_to_solved(&self, cube: &PocketCube) -> u8
+    {
+        // finds the orientation that puts the down-back-left corner in place
+        let is_in_place = |(index, color): &(usize, Color)| cube.squares[*index] == *color;
+        let orientation =
+            self.orientations.iter().find(|orientation| orientation.fixed_corner.iter().all(is_in_place));
+        match orientation
+        {
+            Some(orientation) =>
+            {
+                // puts the pocket cube on the corners of a Rubik's cube
+                let mut corners_cube = Cube::solved();
+                for (index, color) in cube.squares.iter().enumerate()
+                {
+                    corners_cube.squares[orientation.placement[index]] = *color;
+                }
+                self.corners_heuristic.optimistic_distance_to_solved(&corners_cube)
+            }
+            // the cube is invalid (no corner has the colors of the down-back-left corner)
+            None => 0
+        }
+    }
+
+    fn call_counter(&self) -> Option<&AtomicUsize>
+    {
+        self.corners_heuristic.call_counter()
+    }
+}
+
+impl<H: Heuristic> PocketHeuristic<H>
+{
+    /// wraps a heuristic on the corners of the Rubik's cube
+    pub fn new(corners_heuristic: H) -> PocketHeuristic<H>
+    {
+        PocketHeuristic { corners_heuristic, orientations: all_orientations() }
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Serialize, Deserialize};
+use std::sync::atomic::AtomicUsize;
+use super::Heuristic;
+
+/// wrapper over heuristic that multiplies its distances by a weight (weighted A*)
+/// with a weight above 1, solvers usually expand fewer cubes (but not always as they might go deeper)
+/// WARNING: the heuristic is not admissible anymore, solutions are thus not guaranteed to be optimal
+/// (but they are at most `weight` times longer than an optimal solution)
+#[derive(Serialize, Deserialize)]
+pub struct ScaledHeuristic<H>
+{
+    /// factor by which the distances are multiplied
+    weight: f32,
+    /// wrapped heuristic
+    pub heuristic: H
+}
+
+impl<C, H: Heuristic<C>> Heuristic<C> for ScaledHeuristic<H>
+{
+    fn kind() -> String
+    {
+        format!("scaled ({})", H::kind())
+    }
+
+    /// returns the distance of the wrapped heuristic multiplied by the weight
+    fn optimistic_distance_to_solved(&self, cube: &C) -> u8
+    {
+        let distance = self.heuristic.optimistic_distance_to_solved(cube) as f32;
+        (distance * self.weight).min(u8::MAX as f32) as u8
+    }
+
+    fn optimistic_distance_to_solved_batch(&self, cubes: &[C]) -> Vec<u8>
+    {
+        self.heuristic
+            .optimistic_distance_to_solved_batch(cubes)
+            .into_iter()
+            .map(|distance| ((distance as f32) * self.weight).min(u8::MAX as f32) as u8)
+            .collect()
+    }
+
+    /// the distances might be overestimated by a factor `weight`
+    fn overestimation_factor(&self) -> f32
+    {
+        self.weight * self.heuristic.overestimation_factor()
+    }
+
+    fn call_counter(&self) -> Option<&AtomicUsize>
+    {
+        self.heuristic.call_counter()
+    }
+}
+
+impl<H> ScaledHeuristic<H>
+{
+    /// initialize the heuristic
+    /// weights around 1.2-1.5 usually give short solutions while reducing the number of cubes expanded
+    pub fn new(heuristic: H, weight: f32) -> ScaledHeuristic<H>
+    {
+        assert!(weight >= 1.0, "ScaledHeuristic: the weight should be at least 1");
+        ScaledHeuristic { weight, heuristic }
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Serialize, Deserialize};
+use super::Heuristic;
+use crate::cube::Cube;
+
+/// heuristic counting the squares that are not in place, see `Cube::distance_heuristic_sticker`
+/// it needs no precomputed table but is very weak, it is mostly a baseline to compare other heuristics to
+#[derive(Serialize, Deserialize, Default)]
+pub struct StickerHeuristic;
+
+impl Heuristic for StickerHeuristic
+{
+    fn kind() -> String
+    {
+        "sticker".to_string()
+    }
+
+    fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
+    {
+        cube.distance_heuristic_sticker()
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use crate::cube::{Cube, Color, NB_FACES};
+use crate::cube::{Cube, Color, Symmetry, NB_FACES};
 use crate::cube::coordinates::{Coordinate3D, RotationAxis};
 use super::super::permutations::{nb_permutations, decimal_from_permutation};
 use super::Encoder;
@@ -18,6 +18,8 @@ pub struct CornerEncoder
 
 impl Encoder for CornerEncoder
 {
+    const NAME: &'static str = "corners";
+
     /// initializes the encoder
     fn new() -> Self
     {
@@ -41,26 +43,23 @@ impl Encoder for CornerEncoder
     /// combines both into a single number
     fn encode(&self, cube: &Cube) -> usize
     {
-        let mut total_orientation_index = 0;
-        let mut permutation = [0; Self::NB_CORNERS];
-        for (i, (i1, i2, i3)) in self.corners_1D_indexes.iter().enumerate()
-        {
-            let triplet_index = CornerEncoder::index_of_color_triplet(cube.squares[*i1],
-                                                                      cube.squares[*i2],
-                                                                      cube.squares[*i3]);
-            let (corner_index, orientation_index) =
-                self.corner_and_orientation_of_color_triplet_table[triplet_index];
-            permutation[i] = corner_index;
-            total_orientation_index = total_orientation_index * Self::NB_ORIENTATIONS + orientation_index;
-        }
+        let (permutation, orientations) = self.corners(cube);
         // ignores the last corner as its orientation is given by the other corners
-        total_orientation_index /= Self::NB_ORIENTATIONS;
+        let total_orientation_index = orientations.iter()
+                                                  .take(Self::NB_CORNERS - 1)
+                                                  .fold(0, |total, o| total * Self::NB_ORIENTATIONS + o);
         // converts the permutation into an index
         let permutation_index = decimal_from_permutation(&permutation);
         // merges the two indexes
         let nb_orientable_corners = Self::NB_CORNERS - 1;
         permutation_index * Self::NB_ORIENTATIONS.pow(nb_orientable_corners as u32) + total_orientation_index
     }
+
+    /// symmetries send corners to corners, they are all compatible with the encoder
+    fn is_compatible_with(&self, _symmetry: &Symmetry) -> bool
+    {
+        true
+    }
 }
 
 impl CornerEncoder
@@ -83,6 +82,24 @@ impl CornerEncoder
     //-------------------------------------------------------------------------
     // INDEXING TABLES
 
+    /// returns the index of the corner found at each position of the cube and its orientation
+    fn corners(&self, cube: &Cube) -> ([u8; Self::NB_CORNERS], [usize; Self::NB_CORNERS])
+    {
+        let mut permutation = [0; Self::NB_CORNERS];
+        let mut orientations = [0; Self::NB_CORNERS];
+        for (i, (i1, i2, i3)) in self.corners_1D_indexes.iter().enumerate()
+        {
+            let triplet_index = CornerEncoder::index_of_color_triplet(cube.squares[*i1],
+                                                                      cube.squares[*i2],
+                                                                      cube.squares[*i3]);
+            let (corner_index, orientation_index) =
+                self.corner_and_orientation_of_color_triplet_table[triplet_index];
+            permutation[i] = corner_index;
+            orientations[i] = orientation_index;
+        }
+        (permutation, orientations)
+    }
+
     /// turns a triplet of colors into an index
     fn index_of_color_triplet(c1: Color, c2: Color, c3: Color) -> usize
     {
@@ -153,3 +170,112 @@ impl CornerEncoder
         corners_coordinates
     }
 }
+
+/// alternative layout for the corners codes, with the same number of indexes as `CornerEncoder`
+/// the position and orientation of each corner are stored next to one another (instead of storing all the positions
+/// then all the orientations) and the corners of the right face are stored in the least significant digits
+/// thus the cubes that differ by a turn of the right face share all but their last digits and are close in memory
+/// (within 648 indexes of one another) which gives more cache friendly accesses when building and querying the table
+///
+/// NOTE: the other faces do not benefit from this layout (and the middle layers do not move the corners)
+/// on a single core, building the full table took 941s instead of 1199s with `CornerEncoder`
+#[derive(Serialize, Deserialize)]
+pub struct InterleavedCornerEncoder
+{
+    corner_encoder: CornerEncoder
+}
+
+impl Encoder for InterleavedCornerEncoder
+{
+    const NAME: &'static str = "interleaved corners";
+
+    /// initializes the encoder
+    fn new() -> Self
+    {
+        InterleavedCornerEncoder { corner_encoder: CornerEncoder::new() }
+    }
+
+    /// size of the array in which to put the indexes
+    fn nb_indexes() -> usize
+    {
+        CornerEncoder::nb_indexes()
+    }
+
+    /// takes a cube
+    /// gets all of its corners
+    /// visits the positions, finishing with the corners of the right face
+    /// and, for each position, stores the rank of its corner among the corners not seen yet followed by its orientation
+    fn encode(&self, cube: &Cube) -> usize
+    {
+        let (permutation, orientations) = self.corner_encoder.corners(cube);
+        let mut index = 0;
+        // corners already seen, one bit per corner
+        let mut seen_corners: u32 = 0;
+        // the last position is skipped as its rank is always 0 and its orientation is given by the other corners
+        for (nb_corners_seen, position) in
+            Self::POSITIONS_ORDER.iter().take(CornerEncoder::NB_CORNERS - 1).enumerate()
+        {
+            let corner = permutation[*position] as u32;
+            let rank = (corner - (seen_corners & ((1 << corner) - 1)).count_ones()) as usize;
+            seen_corners |= 1 << corner;
+            let nb_corners_left = CornerEncoder::NB_CORNERS - nb_corners_seen;
+            let orientation = orientations[*position];
+            index = (index * nb_corners_left + rank) * CornerEncoder::NB_ORIENTATIONS + orientation;
+        }
+        index
+    }
+
+    /// symmetries send corners to corners, they are all compatible with the encoder
+    fn is_compatible_with(&self, _symmetry: &Symmetry) -> bool
+    {
+        true
+    }
+}
+
+impl InterleavedCornerEncoder
+{
+    /// order in which the positions are stored, most significant first
+    /// the last four positions are the corners of the right face (see `CornerEncoder::compute_corners_1D_indexes`)
+    const POSITIONS_ORDER: [usize; CornerEncoder::NB_CORNERS] = [0, 2, 3, 6, 1, 4, 5, 7];
+}
+
+/// encodes only the positions of the corners, ignoring their orientations
+/// which gives a very small table (8! = 40320 indexes) that can be built instantly
+/// but a weaker heuristic than `CornerEncoder`
+#[derive(Serialize, Deserialize)]
+pub struct CornerPermutationEncoder
+{
+    corner_encoder: CornerEncoder
+}
+
+impl Encoder for CornerPermutationEncoder
+{
+    const NAME: &'static str = "corner permutation";
+
+    /// initializes the encoder
+    fn new() -> Self
+    {
+        CornerPermutationEncoder { corner_encoder: CornerEncoder::new() }
+    }
+
+    /// size of the array in which to put the indexes
+    fn nb_indexes() -> usize
+    {
+        nb_permutations(CornerEncoder::NB_CORNERS)
+    }
+
+    /// takes a cube
+    /// gets the index of the corner at each position
+    /// and converts this permutation into a single value
+    fn encode(&self, cube: &Cube) -> usize
+    {
+        let (permutation, _orientations) = self.corner_encoder.corners(cube);
+        decimal_from_permutation(&permutation)
+    }
+
+    /// symmetries send corners to corners, they are all compatible with the encoder
+    fn is_compatible_with(&self, _symmetry: &Symmetry) -> bool
+    {
+        true
+    }
+}
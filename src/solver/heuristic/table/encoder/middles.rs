@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use crate::cube::{Cube, Color, NB_FACES};
+use crate::cube::{Cube, Color, Symmetry, NB_FACES};
 use crate::cube::coordinates::{Coordinate3D, RotationAxis};
 use super::super::permutations::{nb_partial_permutations, decimal_from_partial_permutation};
 use super::Encoder;
@@ -7,9 +7,11 @@ use super::Encoder;
 //-------------------------------------------------------------------------
 // CONSTANTS
 
-/// number of middles that will be kept
+/// number of middles kept by the default middle tables
 /// Korf recommends 6 for small tables and 7 for large ones
-const NB_MIDDLES_KEPT: usize = 6;
+/// each additional middle makes the table stronger but multiplies its size by about 12:
+/// 5 middles take 3MB, 6 middles take 42.6MB, 7 middles take 511MB and 8 middles take 5.1GB
+pub const DEFAULT_NB_MIDDLES_KEPT: usize = 6;
 /// number of middles
 const NB_MIDDLES: usize = 12;
 /// number of possible orientations for a middle
@@ -18,11 +20,25 @@ const NB_ORIENTATIONS: usize = 2;
 const NB_COLORS: usize = NB_FACES;
 /// number of possible pair of colors
 const NB_COLOR_PAIRS: usize = NB_COLORS * NB_COLORS;
+/// all possible pairs of colors making a middle
+const MIDDLE_PAIRS: [(Color, Color); NB_MIDDLES] = [(Color::Orange, Color::Green),
+                                                    (Color::Green, Color::Red),
+                                                    (Color::Red, Color::Blue),
+                                                    (Color::Blue, Color::Orange),
+                                                    (Color::White, Color::Green),
+                                                    (Color::Green, Color::Yellow),
+                                                    (Color::Yellow, Color::Blue),
+                                                    (Color::Blue, Color::White),
+                                                    (Color::Orange, Color::White),
+                                                    (Color::White, Color::Red),
+                                                    (Color::Red, Color::Yellow),
+                                                    (Color::Yellow, Color::Orange)];
 
 /// used to turn a cube into a single, unique and consecutiv, middles code
 /// and back again
+/// only NB_MIDDLES_KEPT middles are encoded, the first ones if USE_LOWER_MIDDLES is true and the last ones otherwise
 #[derive(Serialize, Deserialize)]
-pub struct MiddleEncoder<const USE_LOWER_MIDDLES: bool>
+pub struct MiddleEncoder<const NB_MIDDLES_KEPT: usize, const USE_LOWER_MIDDLES: bool>
 {
     /// turns a pair index into a middle index and an orientation index
     #[serde(with = "serde_arrays")]
@@ -31,11 +47,28 @@ pub struct MiddleEncoder<const USE_LOWER_MIDDLES: bool>
     middles_1D_indexes: [(usize, usize); NB_MIDDLES]
 }
 
-impl<const USE_LOWER_MIDDLES: bool> Encoder for MiddleEncoder<USE_LOWER_MIDDLES>
+impl<const NB_MIDDLES_KEPT: usize, const USE_LOWER_MIDDLES: bool> Encoder
+    for MiddleEncoder<NB_MIDDLES_KEPT, USE_LOWER_MIDDLES>
 {
+    const NAME: &'static str = if USE_LOWER_MIDDLES
+    {
+        "lower middles"
+    }
+    else
+    {
+        "upper middles"
+    };
+
+    /// the number of middles kept changes the indices, it is thus part of the kind
+    fn kind() -> String
+    {
+        format!("{} ({} kept)", Self::NAME, NB_MIDDLES_KEPT)
+    }
+
     /// initializes the encoder
     fn new() -> Self
     {
+        assert!(NB_MIDDLES_KEPT <= NB_MIDDLES, "MiddleEncoder: there are only {} middles", NB_MIDDLES);
         let middle_and_orientation_of_color_pair_table = Self::compute_table_middle_of_pair();
         let middles_1D_indexes = Self::compute_middles_1D_indexes();
         MiddleEncoder { middle_and_orientation_of_color_pair_table, middles_1D_indexes }
@@ -55,16 +88,12 @@ impl<const USE_LOWER_MIDDLES: bool> Encoder for MiddleEncoder<USE_LOWER_MIDDLES>
     /// combines both into a single number
     fn encode(&self, cube: &Cube) -> usize
     {
+        debug_assert!(self.try_encode(cube).is_some(), "encode: the cube contains invalid middles");
         let mut total_orientation_index = 0;
         let mut permutation = [0; NB_MIDDLES_KEPT]; // (middle_index -> position_index)
         for (i, (i1, i2)) in self.middles_1D_indexes.iter().enumerate()
         {
             let pair_index = Self::index_of_color_pair(cube.squares[*i1], cube.squares[*i2]);
-            // we pass the pair if it is invalid (leftover from a partial decoding)
-            if pair_index >= NB_COLOR_PAIRS
-            {
-                continue;
-            }
             let (middle_index, orientation_index) =
                 self.middle_and_orientation_of_color_pair_table[pair_index];
             let middle_index = middle_index as usize;
@@ -77,13 +106,56 @@ impl<const USE_LOWER_MIDDLES: bool> Encoder for MiddleEncoder<USE_LOWER_MIDDLES>
                     orientation_index * NB_ORIENTATIONS.pow(shifted_middle_index as u32);
             }
         }
-        let permutation_index =
-            decimal_from_partial_permutation::<{ NB_MIDDLES }, { NB_MIDDLES_KEPT }>(&permutation);
-        permutation_index + total_orientation_index * nb_partial_permutations(NB_MIDDLES_KEPT, NB_MIDDLES)
+        Self::index_of_middles(&permutation, total_orientation_index)
+    }
+
+    /// same as `encode` but checks that all the pairs of colors are actual middles
+    /// and that each kept middle appears exactly once
+    fn try_encode(&self, cube: &Cube) -> Option<usize>
+    {
+        let mut total_orientation_index = 0;
+        let mut permutation = [0; NB_MIDDLES_KEPT]; // (middle_index -> position_index)
+        let mut is_middle_seen = [false; NB_MIDDLES];
+        for (i, (i1, i2)) in self.middles_1D_indexes.iter().enumerate()
+        {
+            let (middle_index, orientation_index) = self.middle_of_color_pair(cube.squares[*i1],
+                                                                              cube.squares[*i2])?;
+            if std::mem::replace(&mut is_middle_seen[middle_index], true)
+            {
+                return None;
+            }
+            let shifted_middle_index = Self::shift_middle_index(middle_index);
+            if shifted_middle_index < NB_MIDDLES_KEPT
+            {
+                permutation[shifted_middle_index] = i as u8;
+                total_orientation_index +=
+                    orientation_index * NB_ORIENTATIONS.pow(shifted_middle_index as u32);
+            }
+        }
+        Some(Self::index_of_middles(&permutation, total_orientation_index))
+    }
+
+    /// a symmetry is compatible if it sends the middles that are kept onto middles that are kept
+    fn is_compatible_with(&self, symmetry: &Symmetry) -> bool
+    {
+        for (middle_index, (c1, c2)) in MIDDLE_PAIRS.into_iter().enumerate()
+        {
+            if Self::is_kept(middle_index)
+            {
+                let pair_index = Self::index_of_color_pair(symmetry.map_color(c1), symmetry.map_color(c2));
+                let (new_middle_index, _) = self.middle_and_orientation_of_color_pair_table[pair_index];
+                if !Self::is_kept(new_middle_index as usize)
+                {
+                    return false;
+                }
+            }
+        }
+        true
     }
 }
 
-impl<const USE_LOWER_MIDDLES: bool> MiddleEncoder<USE_LOWER_MIDDLES>
+impl<const NB_MIDDLES_KEPT: usize, const USE_LOWER_MIDDLES: bool>
+    MiddleEncoder<NB_MIDDLES_KEPT, USE_LOWER_MIDDLES>
 {
     //-------------------------------------------------------------------------
     // PRECOMPUTATION
@@ -99,23 +171,9 @@ impl<const USE_LOWER_MIDDLES: bool> MiddleEncoder<USE_LOWER_MIDDLES>
     /// computes a table which associate the index of a color triplet (representing a middle) with a middle index and an orientation
     fn compute_table_middle_of_pair() -> [(u8, usize); NB_COLOR_PAIRS]
     {
-        // all possible pairs of colors making a middle
-        let middle_pairs = vec![(Color::Orange, Color::Green),
-                                (Color::Green, Color::Red),
-                                (Color::Red, Color::Blue),
-                                (Color::Blue, Color::Orange),
-                                (Color::White, Color::Green),
-                                (Color::Green, Color::Yellow),
-                                (Color::Yellow, Color::Blue),
-                                (Color::Blue, Color::White),
-                                (Color::Orange, Color::White),
-                                (Color::White, Color::Red),
-                                (Color::Red, Color::Yellow),
-                                (Color::Yellow, Color::Orange)];
-
         // builds the table
         let mut t2co = [(0, 0); NB_COLOR_PAIRS];
-        for (middle_index, (c1, c2)) in middle_pairs.into_iter().enumerate()
+        for (middle_index, (c1, c2)) in MIDDLE_PAIRS.into_iter().enumerate()
         {
             // all possible permutations of the tree colors
             let middle_index = middle_index as u8;
@@ -178,6 +236,34 @@ impl<const USE_LOWER_MIDDLES: bool> MiddleEncoder<USE_LOWER_MIDDLES>
         middles_coordinates
     }
 
+    /// returns the middle index and orientation index of a pair of colors
+    /// or None if the pair of colors does not correspond to an actual middle
+    fn middle_of_color_pair(&self, c1: Color, c2: Color) -> Option<(usize, usize)>
+    {
+        if (c1 as usize >= NB_COLORS) || (c2 as usize >= NB_COLORS)
+        {
+            return None;
+        }
+        let (middle_index, orientation_index) =
+            self.middle_and_orientation_of_color_pair_table[Self::index_of_color_pair(c1, c2)];
+        let middle_index = middle_index as usize;
+        // pairs that are not middles share the entry of the first middle, we check that we got the actual middle
+        let (first_color, second_color) = MIDDLE_PAIRS[middle_index];
+        let is_middle = match orientation_index
+        {
+            0 => (c1, c2) == (first_color, second_color),
+            _ => (c1, c2) == (second_color, first_color)
+        };
+        is_middle.then_some((middle_index, orientation_index))
+    }
+
+    /// combines the positions of the kept middles and their orientations into a single index
+    fn index_of_middles(permutation: &[u8; NB_MIDDLES_KEPT], total_orientation_index: usize) -> usize
+    {
+        let permutation_index = decimal_from_partial_permutation::<NB_MIDDLES, NB_MIDDLES_KEPT>(permutation);
+        permutation_index + total_orientation_index * nb_partial_permutations(NB_MIDDLES_KEPT, NB_MIDDLES)
+    }
+
     /// takes a middle index and shifts it so that it is one of the first NB_MIDDLES_KEPT indexes
     /// (depending on whether we are keeping the first or last middles)
     /// note that applying this transformation twice cancels it
@@ -194,4 +280,10 @@ impl<const USE_LOWER_MIDDLES: bool> MiddleEncoder<USE_LOWER_MIDDLES>
             NB_MIDDLES - 1 - middle_index
         }
     }
+
+    /// returns true if the middle is one of the middles kept by the encoder
+    fn is_kept(middle_index: usize) -> bool
+    {
+        Self::shift_middle_index(middle_index) < NB_MIDDLES_KEPT
+    }
 }
@@ -1,14 +1,28 @@
 use serde::{Serialize, de::DeserializeOwned};
-use crate::cube::Cube;
+use crate::cube::{Cube, Symmetry};
 mod corners;
 mod middles;
-pub use corners::CornerEncoder;
-pub use middles::MiddleEncoder;
+mod orientation;
+mod two_phase;
+pub use corners::{CornerEncoder, InterleavedCornerEncoder, CornerPermutationEncoder};
+pub use middles::{MiddleEncoder, DEFAULT_NB_MIDDLES_KEPT};
+pub use orientation::{MiddleOrientationEncoder, CornerOrientationEncoder};
+pub use two_phase::{PhaseOneEncoder, PhaseTwoEncoder};
 
 /// used to turn a cube into an index into an array
 /// garanties that the index will be continuous in memory
 pub trait Encoder: Serialize + DeserializeOwned + Sized
 {
+    /// name of the encoder, stored in the header of the files holding its tables
+    /// NOTE: two encoders producing different indices should never share a name
+    const NAME: &'static str;
+
+    /// identifies the encoder, and any parameter that changes its indices, in the header of files
+    fn kind() -> String
+    {
+        Self::NAME.to_string()
+    }
+
     /// initializes the encoder
     fn new() -> Self;
 
@@ -16,5 +30,20 @@ pub trait Encoder: Serialize + DeserializeOwned + Sized
     fn nb_indexes() -> usize;
 
     /// encodes a cube as an index
+    /// the cube is trusted to be valid, use `try_encode` for cubes coming from the outside (such as `Cube::from_facelets`)
     fn encode(&self, cube: &Cube) -> usize;
+
+    /// encodes a cube as an index
+    /// returns None if the cube is not valid (and would thus produce a meaningless index)
+    fn try_encode(&self, cube: &Cube) -> Option<usize>
+    {
+        cube.is_valid().ok().map(|()| self.encode(cube))
+    }
+
+    /// returns true if cubes sharing an index still share an index once conjugated by the symmetry
+    /// meaning that the symmetry can be used to reduce the size of the table (see `SymmetricTableHeuristic`)
+    fn is_compatible_with(&self, _symmetry: &Symmetry) -> bool
+    {
+        false
+    }
 }
@@ -0,0 +1,74 @@
+use serde::{Serialize, Deserialize};
+use crate::cube::Cube;
+use crate::cube::cubies::{Cubies, NB_CORNERS, NB_CORNER_ORIENTATIONS, NB_MIDDLES, NB_MIDDLE_ORIENTATIONS};
+use super::Encoder;
+
+/// used to turn a cube into a single, unique and consecutiv, code describing the orientation of its middles
+/// the permutation of the pieces is ignored which makes for a very small table
+#[derive(Serialize, Deserialize)]
+pub struct MiddleOrientationEncoder;
+
+impl Encoder for MiddleOrientationEncoder
+{
+    const NAME: &'static str = "middle orientation";
+
+    /// initializes the encoder
+    fn new() -> Self
+    {
+        MiddleOrientationEncoder
+    }
+
+    /// size of the array in which to put the indexes
+    fn nb_indexes() -> usize
+    {
+        // the orientation of the last middle is fixed given the others
+        NB_MIDDLE_ORIENTATIONS.pow((NB_MIDDLES - 1) as u32)
+    }
+
+    /// takes a cube
+    /// gets the orientation of its middles
+    /// converts them, ignoring the last middle, into a single value
+    fn encode(&self, cube: &Cube) -> usize
+    {
+        let cubies = Cubies::from_cube(cube).expect("encode: the cube is not made of valid pieces");
+        cubies.middle_orientation
+              .iter()
+              .take(NB_MIDDLES - 1)
+              .fold(0, |index, orientation| index * NB_MIDDLE_ORIENTATIONS + (*orientation as usize))
+    }
+}
+
+/// used to turn a cube into a single, unique and consecutiv, code describing the orientation of its corners
+/// the permutation of the pieces is ignored which makes for a very small table
+#[derive(Serialize, Deserialize)]
+pub struct CornerOrientationEncoder;
+
+impl Encoder for CornerOrientationEncoder
+{
+    const NAME: &'static str = "corner orientation";
+
+    /// initializes the encoder
+    fn new() -> Self
+    {
+        CornerOrientationEncoder
+    }
+
+    /// size of the array in which to put the indexes
+    fn nb_indexes() -> usize
+    {
+        // the orientation of the last corner is fixed given the others
+        NB_CORNER_ORIENTATIONS.pow((NB_CORNERS - 1) as u32)
+    }
+
+    /// takes a cube
+    /// gets the orientation of its corners
+    /// converts them, ignoring the last corner, into a single value
+    fn encode(&self, cube: &Cube) -> usize
+    {
+        let cubies = Cubies::from_cube(cube).expect("encode: the cube is not made of valid pieces");
+        cubies.corner_orientation
+              .iter()
+              .take(NB_CORNERS - 1)
+              .fold(0, |index, orientation| index * NB_CORNER_ORIENTATIONS + (*orientation as usize))
+    }
+}
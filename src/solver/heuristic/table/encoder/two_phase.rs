@@ -0,0 +1,161 @@
+use serde::{Serialize, Deserialize};
+use crate::cube::Cube;
+use crate::cube::cubies::{Cubies, NB_CORNERS, NB_CORNER_ORIENTATIONS, NB_MIDDLES, NB_MIDDLE_ORIENTATIONS};
+use super::super::permutations::{nb_permutations, decimal_from_permutation, nb_combinations,
+                                 decimal_from_combination};
+use super::Encoder;
+
+//-------------------------------------------------------------------------
+// CONSTANTS
+
+/// number of middles in the slice between the Up and Down faces
+/// they are the last middles in the cubies ordering
+const NB_SLICE_MIDDLES: usize = 4;
+/// number of middles outside of the slice between the Up and Down faces
+const NB_OTHER_MIDDLES: usize = NB_MIDDLES - NB_SLICE_MIDDLES;
+
+//-------------------------------------------------------------------------
+// PHASE ONE
+
+/// used to turn a cube into a single, unique and consecutiv, code for the first phase of Kociemba's algorithm
+/// encodes the orientation of the corners (or of the middles if USE_CORNERS is false)
+/// and the position of the middles belonging to the slice between the Up and Down faces
+/// all the cubes that can be solved with phase two moves share the same code
+#[derive(Serialize, Deserialize)]
+pub struct PhaseOneEncoder<const USE_CORNERS: bool>;
+
+impl<const USE_CORNERS: bool> Encoder for PhaseOneEncoder<USE_CORNERS>
+{
+    const NAME: &'static str = if USE_CORNERS
+    {
+        "phase one corners"
+    }
+    else
+    {
+        "phase one middles"
+    };
+
+    /// initializes the encoder
+    fn new() -> Self
+    {
+        PhaseOneEncoder
+    }
+
+    /// size of the array in which to put the indexes
+    fn nb_indexes() -> usize
+    {
+        // the orientation of the last piece is fixed given the others
+        let nb_orientation_indexes = if USE_CORNERS
+        {
+            NB_CORNER_ORIENTATIONS.pow((NB_CORNERS - 1) as u32)
+        }
+        else
+        {
+            NB_MIDDLE_ORIENTATIONS.pow((NB_MIDDLES - 1) as u32)
+        };
+        nb_orientation_indexes * nb_combinations(NB_SLICE_MIDDLES, NB_MIDDLES)
+    }
+
+    /// takes a cube
+    /// gets its pieces
+    /// converts the orientations (ignoring the last piece) in a single value
+    /// and the position of the slice middles in a single value
+    /// combines both into a single number
+    fn encode(&self, cube: &Cube) -> usize
+    {
+        let cubies = cubies_of_cube(cube);
+        let orientation_index = if USE_CORNERS
+        {
+            orientation_index(&cubies.corner_orientation[..(NB_CORNERS - 1)], NB_CORNER_ORIENTATIONS)
+        }
+        else
+        {
+            orientation_index(&cubies.middle_orientation[..(NB_MIDDLES - 1)], NB_MIDDLE_ORIENTATIONS)
+        };
+        // position of the slice middles, ignoring their order
+        let is_slice_middle = cubies.middle_permutation.map(|middle| (middle as usize) >= NB_OTHER_MIDDLES);
+        let slice_index = decimal_from_combination(&is_slice_middle);
+        // merges the two indexes
+        orientation_index * nb_combinations(NB_SLICE_MIDDLES, NB_MIDDLES) + slice_index
+    }
+}
+
+//-------------------------------------------------------------------------
+// PHASE TWO
+
+/// used to turn a cube into a single, unique and consecutiv, code for the second phase of Kociemba's algorithm
+/// encodes the permutation of the corners (or of the middles outside of the slice if USE_CORNERS is false)
+/// and the permutation of the middles inside the slice between the Up and Down faces
+/// WARNING: this assumes that the cube has been brought into the subgroup targeted by the first phase
+#[derive(Serialize, Deserialize)]
+pub struct PhaseTwoEncoder<const USE_CORNERS: bool>;
+
+impl<const USE_CORNERS: bool> Encoder for PhaseTwoEncoder<USE_CORNERS>
+{
+    const NAME: &'static str = if USE_CORNERS
+    {
+        "phase two corners"
+    }
+    else
+    {
+        "phase two middles"
+    };
+
+    /// initializes the encoder
+    fn new() -> Self
+    {
+        PhaseTwoEncoder
+    }
+
+    /// size of the array in which to put the indexes
+    fn nb_indexes() -> usize
+    {
+        let nb_pieces = if USE_CORNERS { NB_CORNERS } else { NB_OTHER_MIDDLES };
+        nb_permutations(nb_pieces) * nb_permutations(NB_SLICE_MIDDLES)
+    }
+
+    /// takes a cube
+    /// gets its pieces
+    /// converts the permutation of the corners (or of the middles outside of the slice) in a single value
+    /// and the permutation of the slice middles in a single value
+    /// combines both into a single number
+    fn encode(&self, cube: &Cube) -> usize
+    {
+        let cubies = cubies_of_cube(cube);
+        let permutation_index = if USE_CORNERS
+        {
+            decimal_from_permutation(&cubies.corner_permutation)
+        }
+        else
+        {
+            let mut permutation = [0; NB_OTHER_MIDDLES];
+            permutation.copy_from_slice(&cubies.middle_permutation[..NB_OTHER_MIDDLES]);
+            decimal_from_permutation(&permutation)
+        };
+        // the slice middles are shifted to start at 0
+        let mut slice_permutation = [0; NB_SLICE_MIDDLES];
+        let slice_middles = &cubies.middle_permutation[NB_OTHER_MIDDLES..];
+        for (shifted_middle, middle) in slice_permutation.iter_mut().zip(slice_middles)
+        {
+            *shifted_middle = middle - (NB_OTHER_MIDDLES as u8);
+        }
+        let slice_index = decimal_from_permutation(&slice_permutation);
+        // merges the two indexes
+        permutation_index * nb_permutations(NB_SLICE_MIDDLES) + slice_index
+    }
+}
+
+//-------------------------------------------------------------------------
+// HELPERS
+
+/// extracts the pieces of a cube
+fn cubies_of_cube(cube: &Cube) -> Cubies
+{
+    Cubies::from_cube(cube).expect("encode: the cube is not made of valid pieces")
+}
+
+/// converts a list of orientations into a single number
+fn orientation_index(orientations: &[u8], nb_orientations: usize) -> usize
+{
+    orientations.iter().fold(0, |index, orientation| index * nb_orientations + (*orientation as usize))
+}
@@ -1,35 +1,119 @@
 use serde::{Serialize, Deserialize, de::DeserializeOwned};
 mod encoder;
 mod permutations;
-use encoder::{Encoder, CornerEncoder, MiddleEncoder};
-use super::Heuristic;
-use crate::cube::{Cube, Move};
-use progressing::{mapping::Bar, Baring};
+mod packed;
+mod storage;
+mod symmetric;
+pub use packed::PackedTableHeuristic;
+pub use symmetric::SymmetricTableHeuristic;
+pub(super) use storage::map_file;
+use storage::TableStorage;
+use encoder::{Encoder, CornerEncoder, InterleavedCornerEncoder, CornerPermutationEncoder, MiddleEncoder,
+              MiddleOrientationEncoder, CornerOrientationEncoder, PhaseOneEncoder, PhaseTwoEncoder};
+pub use encoder::DEFAULT_NB_MIDDLES_KEPT;
+use super::{Heuristic, LoadError, SaveError, check_mapped_header, save_into, load_from, is_phase_two_move};
+use crate::cube::{Cube, Move, Metric};
+use crate::cube::moves::MoveDescription;
+use crate::solver::{ProgressSink, ProgressEvent, StdoutSink};
 use stopwatch::Stopwatch;
 use rayon::prelude::*;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU8, AtomicI8, AtomicUsize, Ordering};
+use std::fs::{rename, remove_file};
+use memmap2::Mmap;
+use bincode::deserialize_from;
+
+/// distance stored, while building a table, for the cubes whose distance is not known yet
+/// actual distances never reach it: they are bounded by God's number (20 moves, 26 in the quarter turn metric)
+const UNKNOWN_DISTANCE: u8 = u8::MAX;
+
+/// depth of the cubes from which the deeper iterations of the table build start
+/// there are thousands of such cubes (against a few dozen solved cubes) which spreads the work evenly over the threads
+const FRONTIER_DEPTH: u8 = 2;
 
 // some common heuristics
 pub type CornerHeuristic = TableHeuristic<CornerEncoder>;
-pub type LowerMiddleHeuristic = TableHeuristic<MiddleEncoder<true>>;
-pub type UpperMiddleHeuristic = TableHeuristic<MiddleEncoder<false>>;
+// same as the corners heuristic with a more cache friendly layout (see `InterleavedCornerEncoder`)
+pub type InterleavedCornerHeuristic = TableHeuristic<InterleavedCornerEncoder>;
+// tiny table ignoring the orientation of the corners, see `CornerPermutationEncoder`
+pub type CornerPermutationHeuristic = TableHeuristic<CornerPermutationEncoder>;
+pub type MiddleHeuristic<const NB_MIDDLES_KEPT: usize, const USE_LOWER_MIDDLES: bool> =
+    TableHeuristic<MiddleEncoder<NB_MIDDLES_KEPT, USE_LOWER_MIDDLES>>;
+pub type LowerMiddleHeuristic = MiddleHeuristic<DEFAULT_NB_MIDDLES_KEPT, true>;
+pub type UpperMiddleHeuristic = MiddleHeuristic<DEFAULT_NB_MIDDLES_KEPT, false>;
+pub type EdgeOrientationHeuristic = TableHeuristic<MiddleOrientationEncoder>;
+// tiny table ignoring the positions of the corners, see `CornerOrientationEncoder`
+pub type CornerOrientationHeuristic = TableHeuristic<CornerOrientationEncoder>;
+// packed versions of the common heuristics
+pub type PackedCornerHeuristic = PackedTableHeuristic<CornerEncoder>;
+pub type PackedMiddleHeuristic<const NB_MIDDLES_KEPT: usize, const USE_LOWER_MIDDLES: bool> =
+    PackedTableHeuristic<MiddleEncoder<NB_MIDDLES_KEPT, USE_LOWER_MIDDLES>>;
+pub type PackedLowerMiddleHeuristic = PackedMiddleHeuristic<DEFAULT_NB_MIDDLES_KEPT, true>;
+pub type PackedUpperMiddleHeuristic = PackedMiddleHeuristic<DEFAULT_NB_MIDDLES_KEPT, false>;
+// symmetry reduced version of the corners heuristic
+pub type SymmetricCornerHeuristic = SymmetricTableHeuristic<CornerEncoder>;
+// heuristics used by the two-phase algorithm
+pub type PhaseOneCornerHeuristic = TableHeuristic<PhaseOneEncoder<true>>;
+pub type PhaseOneMiddleHeuristic = TableHeuristic<PhaseOneEncoder<false>>;
+pub type PhaseTwoCornerHeuristic = TableHeuristic<PhaseTwoEncoder<true>>;
+pub type PhaseTwoMiddleHeuristic = TableHeuristic<PhaseTwoEncoder<false>>;
+
+/// moves that can be used to build a table, see `TableHeuristic::new_restricted_with_checkpoints`
+/// unlike an arbitrary predicate, it can be stored in a checkpoint so that a build is resumed with the same moves
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveSet
+{
+    /// all moves, as used by `TableHeuristic::new`
+    All,
+    /// quarter turns only, as used by `TableHeuristic::new_qtm`
+    QuarterTurn,
+    /// moves keeping the cube in the phase two subgroup, see `is_phase_two_move`
+    PhaseTwo
+}
+
+impl MoveSet
+{
+    /// returns true if the move belongs to the set when applied to the given cube
+    pub fn is_allowed(self, cube: &Cube, m: &MoveDescription) -> bool
+    {
+        match self
+        {
+            MoveSet::All => true,
+            MoveSet::QuarterTurn => Metric::QuarterTurn.is_single_move(m),
+            MoveSet::PhaseTwo => is_phase_two_move(cube, m)
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct TableHeuristic<E: Encoder>
 {
     #[serde(bound(deserialize = "E: DeserializeOwned"))]
     encoder: E,
-    table: Vec<u8>
+    table: TableStorage
 }
 
 impl<E: Encoder> Heuristic for TableHeuristic<E>
 {
+    fn kind() -> String
+    {
+        format!("table ({})", E::kind())
+    }
+
     /// returns a lower bound on the number of steps before the problem will be solved
     fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
     {
         let index = self.encoder.encode(cube);
         self.table[index]
     }
+
+    /// encodes all cubes before reading the table
+    /// so that the, independent, memory accesses can overlap
+    fn optimistic_distance_to_solved_batch(&self, cubes: &[Cube]) -> Vec<u8>
+    {
+        let indexes: Vec<usize> = cubes.iter().map(|cube| self.encoder.encode(cube)).collect();
+        indexes.into_iter().map(|index| self.table[index]).collect()
+    }
 }
 
 impl<E: Encoder + Sync> TableHeuristic<E>
@@ -37,119 +121,409 @@ impl<E: Encoder + Sync> TableHeuristic<E>
     /// initialize the heuristic in parallel
     pub fn new() -> TableHeuristic<E>
     {
+        Self::new_restricted(|_cube, _move| true)
+    }
+
+    /// initialize the heuristic in parallel, reporting progress to the given sink
+    pub fn new_with_sink<S: ProgressSink>(sink: &S) -> TableHeuristic<E>
+    {
+        Self::new_restricted_with_sink(|_cube, _move| true, sink)
+    }
+
+    /// initialize the heuristic in parallel, in the quarter turn metric (no 180° turns)
+    /// WARNING: the resulting heuristic is only admissible for solvers using the quarter turn metric
+    pub fn new_qtm() -> TableHeuristic<E>
+    {
+        Self::new_qtm_with_sink(&StdoutSink)
+    }
+
+    /// initialize the heuristic in parallel, in the quarter turn metric, reporting progress to the given sink
+    pub fn new_qtm_with_sink<S: ProgressSink>(sink: &S) -> TableHeuristic<E>
+    {
+        Self::new_restricted_with_sink(|_cube, m| Metric::QuarterTurn.is_single_move(m), sink)
+    }
+
+    /// initialize the heuristic in parallel, only using the moves for which `is_move_allowed` returns true
+    /// this is useful when the heuristic will be used by a solver that cannot use all moves
+    pub fn new_restricted<F: Fn(&Cube, &MoveDescription) -> bool + Sync>(is_move_allowed: F)
+                                                                         -> TableHeuristic<E>
+    {
+        Self::new_restricted_with_sink(is_move_allowed, &StdoutSink)
+    }
+
+    /// initialize the heuristic in parallel, only using the moves for which `is_move_allowed` returns true
+    /// reports progress to the given sink
+    pub fn new_restricted_with_sink<F: Fn(&Cube, &MoveDescription) -> bool + Sync, S: ProgressSink>(
+        is_move_allowed: F,
+        sink: &S)
+        -> TableHeuristic<E>
+    {
+        // no checkpoint is saved, the move set stored in checkpoints is thus never read and nothing can fail
+        let checkpoint = Checkpoint::empty(MoveSet::All, E::nb_indexes());
+        Self::build(is_move_allowed, sink, checkpoint, None)
+            .expect("new_restricted_with_sink: no checkpoint should be written")
+    }
+
+    /// initialize the heuristic in parallel, saving its state to the given file after each depth
+    /// so that the computation can be continued with `resume` if it is interrupted
+    /// the file is deleted once the table is complete
+    /// returns an error if a checkpoint cannot be written
+    pub fn new_with_checkpoints(file_name: &str) -> Result<TableHeuristic<E>, SaveError>
+    {
+        Self::new_restricted_with_checkpoints(MoveSet::All, file_name)
+    }
+
+    /// initialize the heuristic in parallel, only using the given moves,
+    /// saving its state (including the moves) to the given file after each depth, see `new_with_checkpoints`
+    pub fn new_restricted_with_checkpoints(move_set: MoveSet,
+                                           file_name: &str)
+                                           -> Result<TableHeuristic<E>, SaveError>
+    {
+        let checkpoint = Checkpoint::empty(move_set, E::nb_indexes());
+        Self::build(|cube, m| move_set.is_allowed(cube, m), &StdoutSink, checkpoint, Some(file_name))
+    }
+
+    /// continues the computation of a table interrupted while running `new_with_checkpoints`
+    /// (or `new_restricted_with_checkpoints`, the moves are read from the checkpoint) from the last depth saved in the given file
+    /// returns an error if the checkpoint is missing, corrupted, belongs to another table or cannot be updated
+    pub fn resume(file_name: &str) -> Result<TableHeuristic<E>, LoadError>
+    {
+        let checkpoint = Checkpoint::load(&Self::checkpoint_kind(), file_name)?;
+        if checkpoint.table.len() != E::nb_indexes()
+        {
+            let message = format!("{} entries instead of {}", checkpoint.table.len(), E::nb_indexes());
+            return Err(LoadError::invalid_content(file_name, message));
+        }
+        let move_set = checkpoint.move_set;
+        Self::build(|cube, m| move_set.is_allowed(cube, m), &StdoutSink, checkpoint, Some(file_name))
+            .map_err(LoadError::from_save_error)
+    }
+
+    /// identifies the checkpoints of this table in the header of their files
+    fn checkpoint_kind() -> String
+    {
+        format!("checkpoint ({})", Self::kind())
+    }
+
+    /// fills the table, starting from the given checkpoint
+    /// if a file name is given, saves a checkpoint into it after each depth
+    /// returns an error if a checkpoint cannot be written (it cannot fail without a file name)
+    fn build<F: Fn(&Cube, &MoveDescription) -> bool + Sync, S: ProgressSink>(
+        is_move_allowed: F,
+        sink: &S,
+        checkpoint: Checkpoint,
+        checkpoint_file: Option<&str>)
+        -> Result<TableHeuristic<E>, SaveError>
+    {
+        let move_set = checkpoint.move_set;
         // initializes the table and encoder
         let encoder = E::new();
         let table_size = E::nb_indexes();
-        let table: Vec<AtomicU8> = (0..table_size).map(|_| AtomicU8::new(u8::MAX)).collect();
+        let table: Vec<AtomicU8> = checkpoint.table.into_iter().map(AtomicU8::new).collect();
 
-        // progress bar to track progress
-        let mut progress_bar = Bar::with_range(0, table_size).timed();
+        // used to time the computation
         let mut timer = Stopwatch::start_new();
 
         // uses an iterative deepening search to fill the table
-        let solved_cubes = Cube::all_solved_cubes();
+        let solved_cubes = Cube::solved_cubes();
         let moves = Move::all_moves();
-        let mut current_table_size = 0;
+        let frontier = Self::frontier(solved_cubes, &moves, &is_move_allowed, &encoder);
+        let mut current_table_size = checkpoint.current_table_size;
         // depth left when exploring the various cubes
+        // NOTE: values from previous depths never prune the current depth, we can thus start from scratch when resuming
         let depth_cubes: Vec<AtomicI8> = (0..table_size).map(|_| AtomicI8::new(-1)).collect();
-        for depth in 0..
+        for depth in checkpoint.depth..
         {
+            // a distance equal to the sentinel would be indistinguishable from an unknown distance
+            assert!(depth < UNKNOWN_DISTANCE, "build: distances are too large to be stored in the table");
             // iterates at the given depth from all solved cubes
+            // or, once deep enough, from the frontier (as all paths of length `depth` go through it)
+            let (starting_cubes, starting_depth) = if depth < FRONTIER_DEPTH
+            {
+                (solved_cubes, 0)
+            }
+            else
+            {
+                (frontier.as_slice(), FRONTIER_DEPTH)
+            };
+            let iteration = Iteration { moves: &moves,
+                                        is_move_allowed: &is_move_allowed,
+                                        encoder: &encoder,
+                                        depth_cubes: &depth_cubes,
+                                        table: &table,
+                                        max_depth: depth };
             let nb_new_cubes = AtomicUsize::new(0);
-            solved_cubes.par_iter().for_each(|cube| {
-                                       let mut nb_new_cubes_thread = 0;
-                                       Self::iterative_deepening(cube,
-                                                                 &moves,
-                                                                 &depth_cubes,
-                                                                 &table,
-                                                                 &mut nb_new_cubes_thread,
-                                                                 &encoder,
-                                                                 0,
-                                                                 depth);
-                                       nb_new_cubes.fetch_add(nb_new_cubes_thread, Ordering::Relaxed);
-                                   });
+            starting_cubes.par_iter().for_each(|cube| {
+                                         let mut nb_new_cubes_thread = 0;
+                                         Self::iterative_deepening(cube,
+                                                                   &iteration,
+                                                                   &mut nb_new_cubes_thread,
+                                                                   starting_depth);
+                                         nb_new_cubes.fetch_add(nb_new_cubes_thread, Ordering::Relaxed);
+                                     });
             let nb_new_cubes = nb_new_cubes.into_inner();
 
             // take into account the fact that the table size might be approximative
             current_table_size += nb_new_cubes;
-            let stopping_condition = (current_table_size >= table_size)
-                                     && table.par_iter()
-                                             .all(|distance| distance.load(Ordering::Relaxed) < u8::MAX);
-            // displays the current result
+            let is_known = |distance: &AtomicU8| distance.load(Ordering::Relaxed) != UNKNOWN_DISTANCE;
+            let stopping_condition = (current_table_size >= table_size) && table.par_iter().all(is_known);
+            // reports the current result
             if stopping_condition
             {
-                // display final informations on the table
+                // final informations on the table
                 timer.stop();
-                println!("Table done! (maximum distance:{} table size:{} computing time:{:?})",
-                         depth,
-                         table_size,
-                         timer.elapsed());
+                sink.report(ProgressEvent::TableCompleted { max_distance: depth as usize,
+                                                            table_size,
+                                                            elapsed: timer.elapsed() });
+                // the checkpoint is not needed anymore
+                if let Some(file_name) = checkpoint_file
+                {
+                    remove_file(file_name).map_err(|error| SaveError::unwritable(file_name, error))?;
+                }
                 break;
             }
             else
             {
-                // displays information on the current depth
-                progress_bar.set(current_table_size);
-                println!("Table: did distance {} {} in {:?}", depth, progress_bar, timer.elapsed());
+                // information on the current depth
+                sink.report(ProgressEvent::TableDepthCompleted { depth: depth as usize,
+                                                                 nb_known_cubes: current_table_size,
+                                                                 table_size,
+                                                                 elapsed: timer.elapsed() });
+                // saves the table so far
+                if let Some(file_name) = checkpoint_file
+                {
+                    let table = table.par_iter().map(|distance| distance.load(Ordering::Relaxed)).collect();
+                    let checkpoint = Checkpoint { move_set, depth: depth + 1, current_table_size, table };
+                    checkpoint.save(&Self::checkpoint_kind(), file_name)?;
+                }
             }
         }
 
         // removes the options
         let table: Vec<u8> =
             table.into_par_iter().map(|atomic_distance| atomic_distance.into_inner()).collect();
-        TableHeuristic { encoder, table }
+        let heuristic = TableHeuristic { encoder, table: TableStorage::InMemory(table) };
+
+        // reports the distribution of the distances
+        for (distance, nb_entries) in heuristic.value_histogram().into_iter().enumerate()
+        {
+            if nb_entries > 0
+            {
+                sink.report(ProgressEvent::TableDistanceCount { distance, nb_entries, table_size });
+            }
+        }
+        Ok(heuristic)
+    }
+
+    /// returns the largest distance stored in the table
+    /// as the heuristic is admissible, it is a lower bound on God's number (the largest distance to a solved cube)
+    pub fn max_distance(&self) -> u8
+    {
+        self.table.par_iter().copied().max().unwrap_or(0)
     }
 
-    /// registers all new cubes at depth max_depth
-    fn iterative_deepening(cube: &Cube,
-                           moves: &[Move],
-                           depth_cubes: &[AtomicI8],
-                           table: &[AtomicU8],
-                           nb_new_cubes: &mut usize,
-                           encoder: &E,
-                           depth: u8,
-                           max_depth: u8)
+    /// returns the number of entries of the table (one per index of the encoder)
+    pub fn nb_entries(&self) -> usize
+    {
+        self.table.len()
+    }
+
+    /// returns the number of bytes used by the heuristic (its table, one byte per entry, and its encoder)
+    /// NOTE: a memory-mapped table (see `load_mmap`) is counted fully
+    /// even though the OS only keeps the parts of the file that are being read in memory
+    pub fn memory_bytes(&self) -> usize
+    {
+        self.nb_entries() + std::mem::size_of::<E>()
+    }
+
+    /// returns a lower bound on the number of steps before the cube will be solved
+    /// or None if the cube is not valid (see `Encoder::try_encode`)
+    /// prefer this function to `optimistic_distance_to_solved` for cubes coming from the outside
+    pub fn try_optimistic_distance_to_solved(&self, cube: &Cube) -> Option<u8>
+    {
+        self.encoder.try_encode(cube).map(|index| self.table[index])
+    }
+
+    /// returns the number of entries of the table holding each distance
+    /// useful to evaluate the quality of a heuristic (the larger the distances, the better)
+    pub fn value_histogram(&self) -> [usize; 256]
+    {
+        self.table
+            .par_chunks(1 << 20)
+            .map(|chunk| {
+                let mut histogram = [0; 256];
+                for distance in chunk
+                {
+                    histogram[*distance as usize] += 1;
+                }
+                histogram
+            })
+            .reduce(|| [0; 256],
+                    |mut histogram, other| {
+                        for (count, other_count) in histogram.iter_mut().zip(other)
+                        {
+                            *count += other_count;
+                        }
+                        histogram
+                    })
+    }
+
+    /// loads the heuristic from the given file, memory-mapping the table instead of reading it
+    /// lookups will then read directly from the file, letting the OS decide what should be kept in memory
+    /// NOTE: the file is expected to have been written by `save`,
+    /// the encoder is stored first followed by the table (its length then its raw bytes)
+    /// this layout lets us deserialize the small encoder while keeping the table on disk
+    pub fn load_mmap(file_name: &str) -> Result<TableHeuristic<E>, LoadError>
+    {
+        let file = map_file(file_name)?;
+        let mut offset = check_mapped_header(&file, &Self::kind(), file_name)?;
+        Self::from_mapped_file(&file, file_name, &mut offset)
+    }
+
+    /// reads the heuristic stored, at the given offset, in a memory-mapped file
+    /// updates the offset to point just after the heuristic
+    /// which lets us read several heuristics stored in the same file
+    pub(super) fn from_mapped_file(file: &Arc<Mmap>,
+                                   file_name: &str,
+                                   offset: &mut usize)
+                                   -> Result<TableHeuristic<E>, LoadError>
+    {
+        // deserializes the encoder and the length of the table
+        let mut bytes = &file[*offset..];
+        let invalid_content = |error| LoadError::invalid_content(file_name, error);
+        let encoder = deserialize_from(&mut bytes).map_err(invalid_content)?;
+        let length: u64 = deserialize_from(&mut bytes).map_err(invalid_content)?;
+        let length = length as usize;
+        if bytes.len() < length
+        {
+            return Err(LoadError::invalid_content(file_name, "the file is too short to contain the table"));
+        }
+        // the table starts where the deserialization stopped
+        let table_offset = file.len() - bytes.len();
+        *offset = table_offset + length;
+        let table = TableStorage::Mapped { file: file.clone(), offset: table_offset, length };
+        Ok(TableHeuristic { encoder, table })
+    }
+
+    /// returns the cubes that are exactly `FRONTIER_DEPTH` moves away from the given cubes, keeping one cube per index
+    /// cubes sharing an index have children sharing indexes, keeping a single one thus loses no index
+    fn frontier<F: Fn(&Cube, &MoveDescription) -> bool + Sync>(cubes: &[Cube],
+                                                               moves: &[Move],
+                                                               is_move_allowed: &F,
+                                                               encoder: &E)
+                                                               -> Vec<Cube>
+    {
+        let mut cubes = cubes.to_vec();
+        for _depth in 0..FRONTIER_DEPTH
+        {
+            let mut children: Vec<(usize, Cube)> =
+                cubes.par_iter()
+                     .flat_map_iter(|cube| {
+                         moves.iter()
+                              .filter(|m| is_move_allowed(cube, &m.description))
+                              .map(|m| cube.apply_move(m))
+                     })
+                     .map(|child| (encoder.encode(&child), child))
+                     .collect();
+            children.par_sort_unstable_by_key(|(index, _child)| *index);
+            children.dedup_by_key(|(index, _child)| *index);
+            cubes = children.into_iter().map(|(_index, child)| child).collect();
+        }
+        cubes
+    }
+
+    /// registers all new cubes at depth `iteration.max_depth`
+    fn iterative_deepening<F: Fn(&Cube, &MoveDescription) -> bool>(cube: &Cube,
+                                                                  iteration: &Iteration<E, F>,
+                                                                  nb_new_cubes: &mut usize,
+                                                                  depth: u8)
     {
         // avoids running code on cubes whose children are all known
-        let index = encoder.encode(cube);
-        let depth_left = (max_depth - depth) as i8;
-        if depth_cubes[index].load(Ordering::Relaxed) < depth_left
+        let index = iteration.encoder.encode(cube);
+        let depth_left = (iteration.max_depth - depth) as i8;
+        if iteration.depth_cubes[index].load(Ordering::Relaxed) < depth_left
         {
             // registers cube
             // NOTE: the load then store is not atomic so we might get into this branch by error
             // this does not impact the final table (all thread are at equal depth)
             // but the number of new cubes might be artificially raised because of such errors
-            depth_cubes[index].store(depth_left, Ordering::Relaxed);
+            iteration.depth_cubes[index].store(depth_left, Ordering::Relaxed);
 
-            if depth == max_depth
+            if depth == iteration.max_depth
             {
                 // we are at the depth limit, registers the depth
-                if table[index].load(Ordering::Relaxed) == u8::MAX
+                if iteration.table[index].load(Ordering::Relaxed) == UNKNOWN_DISTANCE
                 {
-                    table[index].store(depth, Ordering::Relaxed);
+                    debug_assert_ne!(depth, UNKNOWN_DISTANCE, "iterative_deepening: the depth is too large");
+                    iteration.table[index].store(depth, Ordering::Relaxed);
                     *nb_new_cubes += 1;
                 }
             }
             else
             {
                 // goes further in depth
-                for m in moves.iter()
+                for m in iteration.moves.iter().filter(|m| (iteration.is_move_allowed)(cube, &m.description))
                 {
                     let child_cube = cube.apply_move(m);
-                    Self::iterative_deepening(&child_cube,
-                                              moves,
-                                              depth_cubes,
-                                              table,
-                                              nb_new_cubes,
-                                              encoder,
-                                              depth + 1,
-                                              max_depth);
+                    Self::iterative_deepening(&child_cube, iteration, nb_new_cubes, depth + 1);
                 }
             }
         }
     }
 }
 
+/// the parameters shared by all the cubes explored while filling a table at a given depth
+struct Iteration<'a, E, F>
+{
+    moves: &'a [Move],
+    is_move_allowed: &'a F,
+    encoder: &'a E,
+    /// depth left when exploring the various cubes
+    depth_cubes: &'a [AtomicI8],
+    table: &'a [AtomicU8],
+    /// depth of the cubes registered by the iteration
+    max_depth: u8
+}
+
+/// state of a table being built, saved between depths so that the computation can be resumed
+/// the header of the file identifies the table (see `TableHeuristic::checkpoint_kind`)
+#[derive(Serialize, Deserialize)]
+struct Checkpoint
+{
+    /// moves used to build the table
+    move_set: MoveSet,
+    /// next depth to explore
+    depth: u8,
+    /// number of cubes found so far
+    current_table_size: usize,
+    /// distances found so far, `UNKNOWN_DISTANCE` for unknown cubes
+    table: Vec<u8>
+}
+
+impl Checkpoint
+{
+    /// checkpoint for a table that has not been started yet
+    fn empty(move_set: MoveSet, table_size: usize) -> Checkpoint
+    {
+        Checkpoint { move_set, depth: 0, current_table_size: 0, table: vec![UNKNOWN_DISTANCE; table_size] }
+    }
+
+    /// saves the checkpoint to the given file, with a header containing the given kind
+    /// writes into a temporary file first so that a crash cannot corrupt the previous checkpoint
+    fn save(&self, kind: &str, file_name: &str) -> Result<(), SaveError>
+    {
+        let temporary_file_name = format!("{}.tmp", file_name);
+        save_into(self, kind, &temporary_file_name)?;
+        rename(&temporary_file_name, file_name).map_err(|error| SaveError::unwritable(file_name, error))
+    }
+
+    /// loads the checkpoint from the given file, checking that its header contains the given kind
+    fn load(kind: &str, file_name: &str) -> Result<Checkpoint, LoadError>
+    {
+        load_from(kind, file_name)
+    }
+}
+
 impl<E: Encoder + Sync> Default for TableHeuristic<E>
 {
     fn default() -> Self
@@ -157,3 +531,72 @@ impl<E: Encoder + Sync> Default for TableHeuristic<E>
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::cube::patterns::SUPERFLIP_DISTANCE;
+    use crate::solver::{NullSink, MAX_SOLUTION_LENGTH};
+
+    /// the checks of the `verify-max-depth` command, on tables small enough to be built by a test:
+    /// no distance exceeds God's number and the bound on the superflip is admissible without being trivial
+    /// (the corners of the superflip are solved, only the edge orientation table sees that it is scrambled)
+    #[test]
+    fn superflip_bound()
+    {
+        let corner_permutation = CornerPermutationHeuristic::new_with_sink(&NullSink);
+        let corner_orientation = CornerOrientationHeuristic::new_with_sink(&NullSink);
+        let edge_orientation = EdgeOrientationHeuristic::new_with_sink(&NullSink);
+        for max_distance in [corner_permutation.max_distance(),
+                             corner_orientation.max_distance(),
+                             edge_orientation.max_distance()]
+        {
+            assert!((max_distance as usize) <= MAX_SOLUTION_LENGTH);
+        }
+        let heuristic = corner_permutation.max_with(corner_orientation).max_with(edge_orientation);
+        let superflip_bound = heuristic.optimistic_distance_to_solved(&Cube::superflip()) as usize;
+        assert!(superflip_bound <= SUPERFLIP_DISTANCE, "the bound on the superflip is not admissible");
+        assert!(superflip_bound > 0, "the tables do not see that the superflip is scrambled");
+    }
+
+    /// two separate builds of a table are byte-identical, even though the deeper iterations run in parallel
+    #[test]
+    fn builds_are_reproducible()
+    {
+        let first = CornerOrientationHeuristic::new_with_sink(&NullSink);
+        let second = CornerOrientationHeuristic::new_with_sink(&NullSink);
+        assert!(first.table[..] == second.table[..]);
+    }
+
+    /// once built, no entry of a table is left at the sentinel and the largest distance is far below it
+    #[test]
+    fn unknown_distance_is_never_stored()
+    {
+        let corner_orientation = CornerOrientationHeuristic::new_with_sink(&NullSink);
+        let edge_orientation = EdgeOrientationHeuristic::new_with_sink(&NullSink);
+        for table in [&corner_orientation.table, &edge_orientation.table]
+        {
+            assert!(table.iter().all(|distance| *distance != UNKNOWN_DISTANCE));
+            assert!(table.iter().all(|distance| (*distance as usize) <= MAX_SOLUTION_LENGTH));
+        }
+    }
+
+    /// a restricted build resumed from a checkpoint uses the moves it was started with
+    /// and a checkpoint cannot be resumed as another table
+    #[test]
+    fn resume_restricted_build()
+    {
+        let file_name = std::env::temp_dir().join("deepcube_resume_restricted_build.bin");
+        let file_name = file_name.to_str().unwrap();
+        let checkpoint = Checkpoint::empty(MoveSet::QuarterTurn, CornerOrientationEncoder::nb_indexes());
+        checkpoint.save(&CornerOrientationHeuristic::checkpoint_kind(), file_name).unwrap();
+        let error = EdgeOrientationHeuristic::resume(file_name).err().unwrap();
+        assert!(matches!(error, LoadError::KindMismatch { .. }));
+        let resumed = CornerOrientationHeuristic::resume(file_name).unwrap();
+        assert!(resumed.table[..] == CornerOrientationHeuristic::new_qtm_with_sink(&NullSink).table[..]);
+        assert!(resumed.table[..] != CornerOrientationHeuristic::new_with_sink(&NullSink).table[..]);
+        // the checkpoint is deleted once the table is complete
+        assert!(matches!(CornerOrientationHeuristic::resume(file_name), Err(LoadError::MissingFile { .. })));
+    }
+}
@@ -35,11 +35,7 @@ pub fn decimal_from_permutation<const NB_ELEMENTS: usize>(permutation: &[u8; NB_
     // the result we will return
     let mut result = 0;
     // represents the indices shifted after each value removal
-    let mut shifted_indices = [0; NB_ELEMENTS];
-    for i in 0..NB_ELEMENTS
-    {
-        shifted_indices[i] = i;
-    }
+    let mut shifted_indices: [usize; NB_ELEMENTS] = std::array::from_fn(|i| i);
     // how many elements are left to process
     let mut nb_elements_left = NB_ELEMENTS;
     // the base by which we are multiplying
@@ -52,9 +48,9 @@ pub fn decimal_from_permutation<const NB_ELEMENTS: usize>(permutation: &[u8; NB_
         // gets shifted index
         let shifted_i = shifted_indices[i];
         // updates shift
-        for j in (i + 1)..NB_ELEMENTS
+        for shifted_index in shifted_indices.iter_mut().skip(i + 1)
         {
-            shifted_indices[j] -= 1;
+            *shifted_index -= 1;
         }
         // updates result
         result += base * shifted_i;
@@ -117,11 +113,7 @@ pub fn decimal_from_partial_permutation<const NB_ELEMENTS: usize, const PERMUTAT
     // the result we will return
     let mut result = 0;
     // represents the indices shifted after each value removal
-    let mut shifted_indices = [0; NB_ELEMENTS];
-    for i in 0..NB_ELEMENTS
-    {
-        shifted_indices[i] = i;
-    }
+    let mut shifted_indices: [usize; NB_ELEMENTS] = std::array::from_fn(|i| i);
     // how many elements are left to process
     let mut nb_elements_left = NB_ELEMENTS;
     // the base by which we are multiplying
@@ -133,9 +125,9 @@ pub fn decimal_from_partial_permutation<const NB_ELEMENTS: usize, const PERMUTAT
         // gets shifted index
         let shifted_i = shifted_indices[i];
         // updates shift
-        for j in (i + 1)..NB_ELEMENTS
+        for shifted_index in shifted_indices.iter_mut().skip(i + 1)
         {
-            shifted_indices[j] -= 1;
+            *shifted_index -= 1;
         }
         // updates result
         result += base * shifted_i;
@@ -174,3 +166,118 @@ pub fn partial_permutation_from_decimal<const NB_ELEMENTS: usize, const PERMUTAT
 
     permutation
 }
+
+//-------------------------------------------------------------------------------------------------
+// COMBINATION
+
+/// returns the number of ways to pick nb_elements among nb_elements_total, ignoring their order
+/// (n choose k computation)
+/// NOTE: this function should be const
+pub fn nb_combinations(nb_elements: usize, nb_elements_total: usize) -> usize
+{
+    nb_partial_permutations(nb_elements, nb_elements_total) / nb_permutations(nb_elements)
+}
+
+/// turns a combination, given as a boolean per element set to true if the element was picked, into a decimal number
+///
+/// uses the combinatorial number system: the k-th picked element (starting from 1), at position i, adds (i choose k)
+pub fn decimal_from_combination<const NB_ELEMENTS_TOTAL: usize>(combination: &[bool; NB_ELEMENTS_TOTAL])
+                                                                -> usize
+{
+    // the result we will return
+    let mut result = 0;
+    // number of elements picked so far
+    let mut nb_picked = 0;
+
+    for (i, is_picked) in combination.iter().enumerate()
+    {
+        if *is_picked
+        {
+            nb_picked += 1;
+            // (i choose k) is 0 when k > i
+            if nb_picked <= i
+            {
+                result += nb_combinations(nb_picked, i);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// checks that encoding then decoding every permutation of NB_ELEMENTS elements is a bijection
+    fn check_permutations<const NB_ELEMENTS: usize>()
+    {
+        for decimal in 0..nb_permutations(NB_ELEMENTS)
+        {
+            let permutation = permutation_from_decimal::<NB_ELEMENTS>(decimal);
+            let mut sorted = permutation;
+            sorted.sort_unstable();
+            assert!(sorted.iter().enumerate().all(|(i, element)| *element as usize == i),
+                    "{:?} is not a permutation",
+                    permutation);
+            assert_eq!(decimal_from_permutation(&permutation), decimal);
+        }
+    }
+
+    /// permutations round-trip through their decimal representation
+    #[test]
+    fn permutation_round_trip()
+    {
+        check_permutations::<1>();
+        check_permutations::<2>();
+        check_permutations::<3>();
+        check_permutations::<4>();
+        check_permutations::<5>();
+        check_permutations::<6>();
+        check_permutations::<7>();
+        check_permutations::<8>();
+    }
+
+    /// checks that decoding every index gives a distinct partial permutation that encodes back to the index
+    /// and that encoding any partial permutation gives an index below `nb_partial_permutations`
+    fn check_partial_permutations<const NB_ELEMENTS: usize, const PERMUTATION_SIZE: usize>()
+    {
+        let nb_indices = nb_partial_permutations(PERMUTATION_SIZE, NB_ELEMENTS);
+        for decimal in 0..nb_indices
+        {
+            let partial_permutation =
+                partial_permutation_from_decimal::<NB_ELEMENTS, PERMUTATION_SIZE>(decimal);
+            let mut is_used = [false; NB_ELEMENTS];
+            for element in partial_permutation.iter().map(|element| *element as usize)
+            {
+                assert!(element < NB_ELEMENTS && !is_used[element], "{:?} is invalid", partial_permutation);
+                is_used[element] = true;
+            }
+            let index =
+                decimal_from_partial_permutation::<NB_ELEMENTS, PERMUTATION_SIZE>(&partial_permutation);
+            assert_eq!(index, decimal);
+        }
+        // the first elements of every full permutation form every partial permutation
+        for decimal in 0..nb_permutations(NB_ELEMENTS)
+        {
+            let permutation = permutation_from_decimal::<NB_ELEMENTS>(decimal);
+            let partial_permutation: [u8; PERMUTATION_SIZE] = std::array::from_fn(|i| permutation[i]);
+            let index =
+                decimal_from_partial_permutation::<NB_ELEMENTS, PERMUTATION_SIZE>(&partial_permutation);
+            assert!(index < nb_indices);
+        }
+    }
+
+    /// partial permutations round-trip through their decimal representation and their indices are in bounds
+    #[test]
+    fn partial_permutation_round_trip()
+    {
+        check_partial_permutations::<5, 2>();
+        check_partial_permutations::<8, 1>();
+        check_partial_permutations::<8, 3>();
+        check_partial_permutations::<8, 7>();
+        check_partial_permutations::<8, 8>();
+        check_partial_permutations::<9, 4>();
+    }
+}
@@ -0,0 +1,63 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use memmap2::Mmap;
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use crate::solver::heuristic::{LoadError, open_file};
+
+/// where the distances of a table are stored
+/// tables are either fully read into memory or read, on demand, from a memory-mapped file
+pub enum TableStorage
+{
+    /// the table has been read into memory
+    InMemory(Vec<u8>),
+    /// the table is a portion of a memory-mapped file
+    /// the file is shared as it can contain several tables
+    Mapped
+    {
+        file: Arc<Mmap>,
+        offset: usize,
+        length: usize
+    }
+}
+
+impl Deref for TableStorage
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8]
+    {
+        match self
+        {
+            TableStorage::InMemory(table) => table,
+            TableStorage::Mapped { file, offset, length } => &file[*offset..(*offset + *length)]
+        }
+    }
+}
+
+/// the table is serialized exactly like a `Vec<u8>` (its length followed by its bytes)
+/// this keeps the files compatible with the ones written before the introduction of memory-mapping
+impl Serialize for TableStorage
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        self.deref().serialize(serializer)
+    }
+}
+
+/// a deserialized table is always stored in memory
+impl<'de> Deserialize<'de> for TableStorage
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TableStorage, D::Error>
+    {
+        Vec::deserialize(deserializer).map(TableStorage::InMemory)
+    }
+}
+
+/// memory-maps the given file so that it can be shared between several tables
+pub fn map_file(file_name: &str) -> Result<Arc<Mmap>, LoadError>
+{
+    let file = open_file(file_name)?;
+    // SAFETY: the file is expected to not be modified while the heuristic is in use
+    let mmap = unsafe { Mmap::map(&file) }.map_err(|error| LoadError::unreadable(file_name, error))?;
+    Ok(Arc::new(mmap))
+}
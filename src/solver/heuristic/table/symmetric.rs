@@ -0,0 +1,169 @@
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use std::collections::{BTreeMap, btree_map::Entry};
+use stopwatch::Stopwatch;
+use rayon::prelude::*;
+use super::encoder::Encoder;
+use super::super::Heuristic;
+use crate::cube::{Cube, Move, Symmetry};
+use crate::solver::{ProgressSink, ProgressEvent, StdoutSink};
+
+/// number of cubes expanded in parallel before their children are registered
+const CHUNK_SIZE: usize = 4096;
+
+/// table heuristic storing a single distance per group of symmetric cubes
+/// cubes are looked up using a canonical index: the smallest index among their conjugates
+/// this divides the memory used by up to the number of symmetries compatible with the encoder (48 for the corners)
+/// at the price of slower lookups (the cube is encoded once per symmetry)
+/// NOTE: as each entry also stores its index, this is only worth it when a lot of symmetries are compatible
+/// the middle encoders only keep half of the middles and are compatible with 4 symmetries which is not enough
+#[derive(Serialize, Deserialize)]
+pub struct SymmetricTableHeuristic<E: Encoder>
+{
+    #[serde(bound(deserialize = "E: DeserializeOwned"))]
+    encoder: E,
+    /// symmetries compatible with the encoder (including the identity)
+    symmetries: Vec<Symmetry>,
+    /// canonical indexes, sorted
+    indexes: Vec<u32>,
+    /// distance associated with each canonical index
+    distances: Vec<u8>
+}
+
+impl<E: Encoder> Heuristic for SymmetricTableHeuristic<E>
+{
+    fn kind() -> String
+    {
+        format!("symmetric table ({})", E::kind())
+    }
+
+    /// returns a lower bound on the number of steps before the problem will be solved
+    fn optimistic_distance_to_solved(&self, cube: &Cube) -> u8
+    {
+        let index = Self::canonical_index(&self.encoder, &self.symmetries, cube);
+        match self.indexes.binary_search(&index)
+        {
+            Ok(position) => self.distances[position],
+            Err(_) => panic!("SymmetricTableHeuristic: the cube cannot be reached from a solved cube")
+        }
+    }
+}
+
+impl<E: Encoder + Sync> SymmetricTableHeuristic<E>
+{
+    /// initialize the heuristic
+    pub fn new() -> SymmetricTableHeuristic<E>
+    {
+        Self::new_with_sink(&StdoutSink)
+    }
+
+    /// initialize the heuristic, reporting progress to the given sink
+    /// uses a breadth first search that only keeps one cube per group of symmetric cubes
+    pub fn new_with_sink<S: ProgressSink>(sink: &S) -> SymmetricTableHeuristic<E>
+    {
+        assert!(E::nb_indexes() <= (u32::MAX as usize) + 1, "SymmetricTableHeuristic: the table is too large");
+        let encoder = E::new();
+        // the identity (first symmetry) is always kept
+        let symmetries: Vec<Symmetry> =
+            Symmetry::all().into_iter()
+                           .enumerate()
+                           .filter(|(index, symmetry)| (*index == 0) || encoder.is_compatible_with(symmetry))
+                           .map(|(_, symmetry)| symmetry)
+                           .collect();
+        // used to display progress, the actual size is a bit larger as some cubes are their own conjugates
+        let estimated_table_size = E::nb_indexes().div_ceil(symmetries.len());
+
+        // used to time the computation
+        let mut timer = Stopwatch::start_new();
+
+        // starts from the solved cubes
+        let moves = Move::all_moves();
+        let mut distances = BTreeMap::new();
+        let mut cubes = Vec::new();
+        for cube in Cube::solved_cubes()
+        {
+            let index = Self::canonical_index(&encoder, &symmetries, cube);
+            if distances.insert(index, 0).is_none()
+            {
+                cubes.push(cube.clone());
+            }
+        }
+
+        // breadth first search, one depth at a time
+        for depth in 1..
+        {
+            let mut new_cubes = Vec::new();
+            for chunk in cubes.chunks(CHUNK_SIZE)
+            {
+                let children: Vec<(u32, Cube)> =
+                    chunk.par_iter()
+                         .flat_map_iter(|cube| {
+                             moves.iter().map(|m| {
+                                             let child = cube.apply_move(m);
+                                             (Self::canonical_index(&encoder, &symmetries, &child), child)
+                                         })
+                         })
+                         .collect();
+                for (index, child) in children
+                {
+                    if let Entry::Vacant(entry) = distances.entry(index)
+                    {
+                        entry.insert(depth);
+                        new_cubes.push(child);
+                    }
+                }
+            }
+
+            // reports the current result
+            if new_cubes.is_empty()
+            {
+                timer.stop();
+                sink.report(ProgressEvent::TableCompleted { max_distance: (depth - 1) as usize,
+                                                            table_size: distances.len(),
+                                                            elapsed: timer.elapsed() });
+                break;
+            }
+            else
+            {
+                sink.report(ProgressEvent::TableDepthCompleted { depth: depth as usize,
+                                                                 nb_known_cubes: distances.len(),
+                                                                 table_size: estimated_table_size
+                                                                                 .max(distances.len()),
+                                                                 elapsed: timer.elapsed() });
+                cubes = new_cubes;
+            }
+        }
+
+        // the map is sorted by index
+        let (indexes, distances) = distances.into_iter().unzip();
+        SymmetricTableHeuristic { encoder, symmetries, indexes, distances }
+    }
+
+    /// number of distances stored in the table
+    pub fn len(&self) -> usize
+    {
+        self.indexes.len()
+    }
+
+    /// returns true if the table is empty
+    pub fn is_empty(&self) -> bool
+    {
+        self.indexes.is_empty()
+    }
+}
+
+impl<E: Encoder> SymmetricTableHeuristic<E>
+{
+    /// returns the smallest index among the conjugates of the cube
+    fn canonical_index(encoder: &E, symmetries: &[Symmetry], cube: &Cube) -> u32
+    {
+        symmetries.iter().map(|symmetry| encoder.encode(&cube.conjugate(symmetry)) as u32).min().unwrap()
+    }
+}
+
+impl<E: Encoder + Sync> Default for SymmetricTableHeuristic<E>
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+use enum_iterator::IntoEnumIterator;
+use super::{Heuristic, PhaseOneCornerHeuristic, PhaseOneMiddleHeuristic, PhaseTwoCornerHeuristic,
+            PhaseTwoMiddleHeuristic};
+use super::table::map_file;
+use super::{LoadError, SaveError, save_into, load_from, check_mapped_header};
+use crate::cube::{Cube, Color, Face, RotationAxis};
+use crate::cube::moves::{Amplitude, MoveDescription};
+use crate::solver::{ProgressSink, StdoutSink};
+// for serialization
+
+/// identifies the two-phase tables in the header of the files they are saved into
+const KIND: &str = "two-phase tables";
+
+/// tables used by Kociemba's two-phase algorithm
+/// the first phase brings the cube into the subgroup of cubes that can be solved with phase two moves
+/// (see `is_phase_two_move`), the second phase solves the cube using only those moves
+#[derive(Serialize, Deserialize)]
+pub struct TwoPhaseTables
+{
+    pub phase_one_corners_heuristic: PhaseOneCornerHeuristic,
+    pub phase_one_middles_heuristic: PhaseOneMiddleHeuristic,
+    pub phase_two_corners_heuristic: PhaseTwoCornerHeuristic,
+    pub phase_two_middles_heuristic: PhaseTwoMiddleHeuristic
+}
+
+impl TwoPhaseTables
+{
+    /// initialize the tables
+    pub fn new() -> TwoPhaseTables
+    {
+        Self::new_with_sink(&StdoutSink)
+    }
+
+    /// initialize the tables, reporting progress to the given sink
+    pub fn new_with_sink<S: ProgressSink>(sink: &S) -> TwoPhaseTables
+    {
+        let phase_one_corners_heuristic = PhaseOneCornerHeuristic::new_with_sink(sink);
+        let phase_one_middles_heuristic = PhaseOneMiddleHeuristic::new_with_sink(sink);
+        let phase_two_corners_heuristic =
+            PhaseTwoCornerHeuristic::new_restricted_with_sink(is_phase_two_move, sink);
+        let phase_two_middles_heuristic =
+            PhaseTwoMiddleHeuristic::new_restricted_with_sink(is_phase_two_move, sink);
+        TwoPhaseTables { phase_one_corners_heuristic,
+                         phase_one_middles_heuristic,
+                         phase_two_corners_heuristic,
+                         phase_two_middles_heuristic }
+    }
+
+    /// returns a lower bound on the number of moves needed to bring the cube into the phase two subgroup
+    /// it is 0 if and only if the cube is already in the subgroup
+    pub fn phase_one_distance(&self, cube: &Cube) -> u8
+    {
+        let corners_distance = self.phase_one_corners_heuristic.optimistic_distance_to_solved(cube);
+        let middles_distance = self.phase_one_middles_heuristic.optimistic_distance_to_solved(cube);
+        corners_distance.max(middles_distance)
+    }
+
+    /// returns a lower bound on the number of phase two moves needed to solve the cube
+    /// WARNING: the cube should be in the phase two subgroup
+    pub fn phase_two_distance(&self, cube: &Cube) -> u8
+    {
+        let corners_distance = self.phase_two_corners_heuristic.optimistic_distance_to_solved(cube);
+        let middles_distance = self.phase_two_middles_heuristic.optimistic_distance_to_solved(cube);
+        corners_distance.max(middles_distance)
+    }
+
+    /// save the tables to the given file
+    /// returns an error if the file cannot be written
+    pub fn save(&self, file_name: &str) -> Result<(), SaveError>
+    {
+        save_into(self, KIND, file_name)
+    }
+
+    /// loads the tables from the given file
+    /// returns an error if the file is missing, corrupted or does not contain the tables
+    pub fn load(file_name: &str) -> Result<TwoPhaseTables, LoadError>
+    {
+        load_from(KIND, file_name)
+    }
+
+    /// loads the tables from the given file, memory-mapping them instead of reading them
+    /// see `TableHeuristic::load_mmap`
+    pub fn load_mmap(file_name: &str) -> Result<TwoPhaseTables, LoadError>
+    {
+        let file = map_file(file_name)?;
+        let mut offset = check_mapped_header(&file, KIND, file_name)?;
+        let phase_one_corners_heuristic =
+            PhaseOneCornerHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        let phase_one_middles_heuristic =
+            PhaseOneMiddleHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        let phase_two_corners_heuristic =
+            PhaseTwoCornerHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        let phase_two_middles_heuristic =
+            PhaseTwoMiddleHeuristic::from_mapped_file(&file, file_name, &mut offset)?;
+        Ok(TwoPhaseTables { phase_one_corners_heuristic,
+                            phase_one_middles_heuristic,
+                            phase_two_corners_heuristic,
+                            phase_two_middles_heuristic })
+    }
+}
+
+impl Default for TwoPhaseTables
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+/// returns true if the move keeps the cube in the phase two subgroup
+/// those are the half turns and the moves rotating around the axis of the white and yellow centers
+/// NOTE: that axis depends on the cube as center layer moves displace the centers
+pub fn is_phase_two_move(cube: &Cube, m: &MoveDescription) -> bool
+{
+    if m.amplitude == Amplitude::Fullturn
+    {
+        return true;
+    }
+    let move_axis = m.kind.axis();
+    let white_face = Face::into_enum_iter().find(|face| cube.get(*face, 1, 1) == Color::White)
+                                          .expect("is_phase_two_move: the cube has no white center");
+    let white_axis = match white_face
+    {
+        Face::Front | Face::Back => RotationAxis::FrontBack,
+        Face::Right | Face::Left => RotationAxis::LeftRight,
+        Face::Down | Face::Up => RotationAxis::DownUp
+    };
+    move_axis == white_axis
+}
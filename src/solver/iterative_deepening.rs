@@ -1,42 +1,64 @@
+use std::time::Duration;
 use stopwatch::Stopwatch;
 use crate::cube::moves::{Move, MoveDescription, MoveKind, Amplitude};
-use crate::cube::Cube;
+use crate::cube::{Cube, CubeIdentifier, Metric};
+use super::{is_out_of_time, already_solved, max_solution_length, MAX_SOLUTION_LENGTH,
+            MAX_SOLUTION_LENGTH_QTM};
+use super::stats::{SolveStats, print_result};
+use super::transposition::TranspositionTable;
+use super::{ProgressSink, ProgressEvent, StdoutSink};
+
+/// the parameters shared by all the cubes explored during an iteration of iterative deepening
+/// along with the counters updated by the iteration
+struct Iteration<'a>
+{
+    moves: &'a [Move],
+    transpositions: &'a mut TranspositionTable<Cube, CubeIdentifier>,
+    timer: &'a Stopwatch,
+    timeout: Duration,
+    /// length of the solutions looked for
+    target_depth: usize,
+    nb_cube_expanded: usize
+}
 
 impl Cube
 {
-    /// returns true if it finds a solution at depth `target_depth`
+    /// returns true if it finds a solution at depth `iteration.target_depth`
     /// path will then contain the path to the solution
+    /// returns false early if we ran out of time
     fn solve_iterative_deepening_rec(cube: Cube,
                                      path: &mut [MoveDescription],
-                                     moves: &[Move],
-                                     nb_cube_expanded: &mut usize,
-                                     depth: usize,
-                                     target_depth: usize)
+                                     iteration: &mut Iteration,
+                                     depth: usize)
                                      -> bool
     {
-        if depth >= target_depth
+        if depth >= iteration.target_depth
         {
             // check if the cube is a solution
             cube.is_solved()
         }
+        else if is_out_of_time(iteration.timer, iteration.timeout, iteration.nb_cube_expanded)
+        {
+            // we ran out of time
+            false
+        }
+        else if iteration.transpositions.is_transposition(&cube, depth)
+        {
+            // this cube has already been explored with at least as many moves left
+            false
+        }
         else
         {
             // expands to the next depth
-            *nb_cube_expanded += 1;
-            for m in moves.iter()
+            iteration.nb_cube_expanded += 1;
+            for m in iteration.moves.iter()
             {
                 // applies a move
                 let child_cube = cube.apply_move(m);
                 // updates the path
                 path[depth] = m.description;
                 // goes one depth further
-                let is_sucess = Cube::solve_iterative_deepening_rec(child_cube,
-                                                                    path,
-                                                                    moves,
-                                                                    nb_cube_expanded,
-                                                                    depth + 1,
-                                                                    target_depth);
-                if is_sucess
+                if Cube::solve_iterative_deepening_rec(child_cube, path, iteration, depth + 1)
                 {
                     return true;
                 }
@@ -51,46 +73,121 @@ impl Cube
     /// - this algorithm has the particularity of not requiring an heuristic
     /// - this algorithm *will* find an optimal solution but might be significantly slow
     ///   as it iteraterates on all possible cubes by increasing depth
-    pub fn solve_iterative_deepening(&self) -> Vec<MoveDescription>
+    ///
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening(&self) -> Option<Vec<MoveDescription>>
+    {
+        self.solve_iterative_deepening_timed(Duration::MAX)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_iterative_deepening_timed(&self, timeout: Duration) -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.iterative_deepening(Metric::HalfTurn, 0, timeout, &StdoutSink);
+        print_result(path, &stats, timeout, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube by trying depth one after the other,
+    /// skipping the cubes that have already been explored with at least as many moves left
+    /// up to `max_nb_cubes` cubes are remembered (each taking a few dozen bytes)
+    /// trading the constant memory use of iterative deepening for a large reduction in the number of cubes expanded
+    pub fn solve_iterative_deepening_with_transpositions(&self,
+                                                         max_nb_cubes: usize)
+                                                         -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) =
+            self.iterative_deepening(Metric::HalfTurn, max_nb_cubes, Duration::MAX, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube by trying depth one after the other, in the quarter turn metric (no 180° turns)
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH_QTM` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening_qtm(&self) -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.iterative_deepening(Metric::QuarterTurn, 0, Duration::MAX, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH_QTM)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_iterative_deepening_with_stats<S: ProgressSink>(&self,
+                                                                 sink: &S)
+                                                                 -> (Option<Vec<MoveDescription>>, SolveStats)
     {
+        self.iterative_deepening(Metric::HalfTurn, 0, Duration::MAX, sink)
+    }
+
+    /// iterative deepening
+    /// returns None if no solution was found in less than `timeout` or in less than `MAX_SOLUTION_LENGTH` moves
+    /// (as measured in the given metric)
+    /// uses a transposition table storing up to `max_nb_transpositions` cubes (0 disables it)
+    fn iterative_deepening<S: ProgressSink>(&self,
+                                            metric: Metric,
+                                            max_nb_transpositions: usize,
+                                            timeout: Duration,
+                                            sink: &S)
+                                            -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
         // used to time the computation
         let timer = Stopwatch::start_new();
         let mut nb_cube_expanded = 0;
         // all moves that can be applied to a cube
-        let moves = Move::all_moves();
+        let moves = Move::all_moves_with_metric(metric);
+        let max_length = max_solution_length(metric);
         let dummy_move = MoveDescription { kind: MoveKind::Front, amplitude: Amplitude::Clockwise };
+        let mut transpositions = TranspositionTable::new(max_nb_transpositions, Cube::to_identifier);
 
         let mut path = Vec::new();
-        for target_depth in 0..
+        for target_depth in 0..=max_length
         {
             // tries to find a solution at the given depth
-            let cube = self.clone();
-            let is_solved = Cube::solve_iterative_deepening_rec(cube,
-                                                                &mut path,
-                                                                &moves,
-                                                                &mut nb_cube_expanded,
-                                                                0,
-                                                                target_depth);
+            // depths seen during the previous iterations are not comparable with the current one
+            transpositions.clear();
+            let mut iteration = Iteration { moves: &moves,
+                                            transpositions: &mut transpositions,
+                                            timer: &timer,
+                                            timeout,
+                                            target_depth,
+                                            nb_cube_expanded: 0 };
+            let is_solved = Cube::solve_iterative_deepening_rec(self.clone(), &mut path, &mut iteration, 0);
+            nb_cube_expanded += iteration.nb_cube_expanded;
 
-            // checks if we reached the target
-            if is_solved
+            // checks if we reached the target or ran out of time
+            if is_solved || (timer.elapsed() > timeout)
             {
-                println!("Done! Found a path of length {} in {:?} ({} cubes expanded / 0 heuristic call)",
-                         target_depth,
-                         timer.elapsed(),
-                         nb_cube_expanded);
-                println!("Path: {:?}", path);
-                return path;
+                let stats = SolveStats { path_length: is_solved.then_some(target_depth),
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls: 0,
+                                         nb_transposition_hits: transpositions.nb_hits,
+                                         max_depth_reached: target_depth,
+                                         elapsed: timer.elapsed() };
+                return (is_solved.then_some(path), stats);
             }
             else
             {
-                // display information on the run
-                println!("Iterative deepening: did distance {} in {:?} ({} cubes expanded / 0 heuristic call)", target_depth, timer.elapsed(), nb_cube_expanded);
+                // reports information on the run
+                sink.report(ProgressEvent::DepthCompleted { solver: "Iterative deepening",
+                                                            depth: target_depth,
+                                                            nb_cube_expanded,
+                                                            nb_heuristic_calls: 0,
+                                                            elapsed: timer.elapsed() });
                 // increases the size of the path for the next iteration
                 path.push(dummy_move);
             }
         }
-        // notes that there is a solution
-        unreachable!("Either a solution exists or the solver will run forever.")
+        // there is no solution short enough, the cube cannot be solved
+        let stats = SolveStats { path_length: None,
+                                 nb_cube_expanded,
+                                 nb_heuristic_calls: 0,
+                                 nb_transposition_hits: transpositions.nb_hits,
+                                 max_depth_reached: max_length,
+                                 elapsed: timer.elapsed() };
+        (None, stats)
     }
 }
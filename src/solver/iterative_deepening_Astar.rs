@@ -1,74 +1,253 @@
+use std::time::Duration;
 use stopwatch::Stopwatch;
-use crate::cube::moves::{Move, MoveDescription, MoveKind, Amplitude};
-use crate::cube::Cube;
-use crate::solver::heuristic::Heuristic;
+use enum_iterator::IntoEnumIterator;
+use crate::cube::moves::{Move, MoveOfSize, MoveDescription, MoveKind, Amplitude};
+use crate::cube::{Cube, CubeOfSize};
+use crate::solver::heuristic::{Heuristic, DynHeuristic, DynHeuristicRef};
+use super::{is_out_of_time, already_solved, MAX_SOLUTION_LENGTH, MAX_SOLUTION_LENGTH_QTM};
+use super::stats::{SolveStats, print_result};
+use super::transposition::TranspositionTable;
+use super::{ProgressSink, ProgressEvent, StdoutSink, NullSink, MoveCosts};
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-impl Cube
+/// returns true if a move of the given kind, following a move of the previous kind, is redundant
+/// (another path of equal or smaller length will reach the same cube)
+/// this is the case when the kind is equal to the previous kind (the moves can be merged)
+/// or when both kinds commute (same axis) and are not in canonical order
+pub(super) fn is_redundant(previous_kind: Option<MoveKind>, kind: MoveKind) -> bool
+{
+    match previous_kind
+    {
+        Some(previous_kind) =>
+        {
+            (previous_kind.axis() == kind.axis()) && ((kind as usize) <= (previous_kind as usize))
+        }
+        None => false
+    }
+}
+
+/// for each kind of move, for each amplitude, true if the move is part of the moves used by the search
+type AvailableMoves = [[bool; Amplitude::VARIANT_COUNT]; MoveKind::VARIANT_COUNT];
+
+/// returns the moves that are part of the given set of moves
+fn available_moves<const SIDE: usize, const NB_SQUARES: usize>(moves: &[MoveOfSize<SIDE, NB_SQUARES>])
+                                                               -> AvailableMoves
+{
+    let mut result = [[false; Amplitude::VARIANT_COUNT]; MoveKind::VARIANT_COUNT];
+    for m in moves
+    {
+        result[m.description.kind as usize][m.description.amplitude as usize] = true;
+    }
+    result
+}
+
+/// returns true if a move, following the previous move, is redundant given the moves used by the search
+/// moves of the same kind are only redundant if they cancel or can be merged into an available move
+/// that does not cost more than the two moves (when the search cannot use half turns,
+/// or when they are expensive, they have to be done as two quarter turns)
+fn is_redundant_with(previous_move: Option<MoveDescription>,
+                     description: MoveDescription,
+                     available_moves: &AvailableMoves,
+                     costs: &MoveCosts)
+                     -> bool
 {
-    /// returns true if it finds a solution at depth `target_depth`
-    /// path will then contain the path to the solution
-    fn solve_iterative_deepening_Astar_rec<H: Heuristic>(cube: Cube,
-                                                         path: &mut [MoveDescription],
-                                                         moves: &[Move],
-                                                         heuristic: &H,
-                                                         nb_cube_expanded: &mut usize,
-                                                         nb_heuristic_calls: &mut usize,
-                                                         depth: usize,
-                                                         target_depth: usize,
-                                                         next_depth: &mut usize)
-                                                         -> bool
+    match previous_move
     {
+        Some(previous_move) if previous_move.kind == description.kind =>
+        {
+            let nb_rotations = previous_move.amplitude.nb_rotations() + description.amplitude.nb_rotations();
+            match Amplitude::from_nb_rotations(nb_rotations)
+            {
+                Some(amplitude) =>
+                {
+                    let merged_move = MoveDescription { kind: description.kind, amplitude };
+                    let unmerged_cost = costs.cost(previous_move) + costs.cost(description);
+                    let is_merge_cheaper = costs.cost(merged_move) <= unmerged_cost;
+                    available_moves[description.kind as usize][amplitude as usize] && is_merge_cheaper
+                }
+                None => true
+            }
+        }
+        _ => is_redundant(previous_move.map(|m| m.kind), description.kind)
+    }
+}
+
+/// the parameters shared by all the cubes explored during an iteration of iterative deepening A*
+/// along with the counters updated by the iteration
+struct Iteration<'a, const SIDE: usize, const NB_SQUARES: usize, H, K: Ord>
+{
+    moves: &'a [MoveOfSize<SIDE, NB_SQUARES>],
+    heuristic: &'a H,
+    /// used to prune redundant moves
+    available_moves: &'a AvailableMoves,
+    costs: &'a MoveCosts,
+    /// if true, the children are explored from the most promising (according to the heuristic) to the least promising
+    is_ordered: bool,
+    timer: &'a Stopwatch,
+    timeout: Duration,
+    /// cost of the solutions looked for
+    target_depth: usize,
+    /// smallest cost, above `target_depth`, seen during the iteration (the target of the next iteration)
+    next_depth: usize,
+    transpositions: &'a mut TranspositionTable<CubeOfSize<SIDE, NB_SQUARES>, K>,
+    nb_cube_expanded: usize,
+    nb_heuristic_calls: usize
+}
+
+/// the settings of a sequential iterative deepening A* search
+struct SearchSettings<C, K: Ord>
+{
+    /// cubes already explored during the current iteration are pruned using this table
+    transpositions: TranspositionTable<C, K>,
+    /// if true, the children are explored from the most promising (according to the heuristic) to the least promising
+    is_ordered: bool,
+    /// the solution found is the cheapest one according to those costs (the shortest one with uniform costs)
+    costs: MoveCosts,
+    /// the search gives up once it has proven that there is no solution of at most `max_length` moves
+    max_length: usize,
+    timeout: Duration
+}
+
+impl<C> SearchSettings<C, ()>
+{
+    /// plain IDA*: no transposition table, children explored in order, uniform costs,
+    /// solutions of up to `MAX_SOLUTION_LENGTH` moves and no timeout
+    fn new() -> SearchSettings<C, ()>
+    {
+        SearchSettings { transpositions: TranspositionTable::disabled(),
+                         is_ordered: false,
+                         costs: MoveCosts::uniform(),
+                         max_length: MAX_SOLUTION_LENGTH,
+                         timeout: Duration::MAX }
+    }
+}
+
+impl<const SIDE: usize, const NB_SQUARES: usize> CubeOfSize<SIDE, NB_SQUARES>
+{
+    /// returns the length of the path to the solution if it finds a solution costing `iteration.target_depth`
+    /// path will then start with the path to the solution
+    /// returns None early if we ran out of time
+    /// `depth` is the number of moves done so far and `cost` their total cost (equal to `depth` with uniform costs)
+    /// `cube_distance` is the heuristic value of the cube, if it was already computed by its parent
+    fn solve_iterative_deepening_Astar_rec<H: Heuristic<Self>, K: Ord>(
+        cube: Self,
+        cube_distance: Option<u8>,
+        path: &mut [MoveDescription],
+        iteration: &mut Iteration<SIDE, NB_SQUARES, H, K>,
+        previous_move: Option<MoveDescription>,
+        depth: usize,
+        cost: usize)
+        -> Option<usize>
+    {
+        let heuristic = iteration.heuristic;
+        let available_moves = iteration.available_moves;
+        let costs = iteration.costs;
         // lower bound on the number of steps needed to do a solve with this move
-        *nb_heuristic_calls += 1;
-        let minimum_final_depth = depth + heuristic.optimistic_distance_to_solved(&cube) as usize;
-        match minimum_final_depth.cmp(&target_depth)
+        let cube_distance = cube_distance.unwrap_or_else(|| {
+                                             iteration.nb_heuristic_calls += 1;
+                                             heuristic.optimistic_distance_to_solved(&cube)
+                                         });
+        // each move left costs at least the cost of the cheapest move
+        let minimum_final_depth = cost + (cube_distance as usize) * costs.min_cost();
+        match minimum_final_depth.cmp(&iteration.target_depth)
         {
             std::cmp::Ordering::Greater =>
             {
                 // minimum_final_depth > target_depth
                 // updates the depth for the next round
-                if minimum_final_depth < *next_depth
+                if minimum_final_depth < iteration.next_depth
                 {
-                    *next_depth = minimum_final_depth;
+                    iteration.next_depth = minimum_final_depth;
                 }
-                false
+                None
             }
             std::cmp::Ordering::Equal if cube.is_solved() =>
             {
                 // minimum_final_depth == target_depth
                 // we found a solution
-                true
+                Some(depth)
+            }
+            _ if is_out_of_time(iteration.timer, iteration.timeout, iteration.nb_cube_expanded) =>
+            {
+                // we ran out of time
+                None
+            }
+            _ if iteration.transpositions.is_transposition(&cube, cost) =>
+            {
+                // this cube has already been explored with at least as many moves left
+                None
+            }
+            _ if iteration.is_ordered =>
+            {
+                // minimum_final_depth <= target_depth
+                // expands to the next depth, starting with the children closest to a solved cube
+                iteration.nb_cube_expanded += 1;
+                let mut children: Vec<(Self, MoveDescription, u8)> =
+                    iteration.moves
+                             .iter()
+                             .filter(|m| {
+                                 !is_redundant_with(previous_move, m.description, available_moves, costs)
+                             })
+                             .map(|m| {
+                                 let child_cube = cube.apply_move(m);
+                                 iteration.nb_heuristic_calls += 1;
+                                 let child_distance = heuristic.optimistic_distance_to_solved(&child_cube);
+                                 (child_cube, m.description, child_distance)
+                             })
+                             .collect();
+                children.sort_by_key(|(_child_cube, _description, child_distance)| *child_distance);
+                for (child_cube, description, child_distance) in children
+                {
+                    // updates the path
+                    path[depth] = description;
+                    let child_cost = cost + costs.cost(description);
+                    // goes one depth further
+                    let solution_length = Self::solve_iterative_deepening_Astar_rec(child_cube,
+                                                                                    Some(child_distance),
+                                                                                    path,
+                                                                                    iteration,
+                                                                                    Some(description),
+                                                                                    depth + 1,
+                                                                                    child_cost);
+                    if solution_length.is_some()
+                    {
+                        return solution_length;
+                    }
+                }
+                // we did not find a solution at the given depth
+                None
             }
             _ =>
             {
                 // minimum_final_depth <= target_depth
                 // expands to the next depth
-                *nb_cube_expanded += 1;
-                for m in moves.iter()
+                iteration.nb_cube_expanded += 1;
+                let is_useful = |m: &&MoveOfSize<SIDE, NB_SQUARES>| {
+                    !is_redundant_with(previous_move, m.description, available_moves, costs)
+                };
+                for m in iteration.moves.iter().filter(is_useful)
                 {
                     // applies a move
                     let child_cube = cube.apply_move(m);
                     // updates the path
                     path[depth] = m.description;
+                    let child_cost = cost + costs.cost(m.description);
                     // goes one depth further
-                    let is_sucess = Cube::solve_iterative_deepening_Astar_rec(child_cube,
-                                                                              path,
-                                                                              moves,
-                                                                              heuristic,
-                                                                              nb_cube_expanded,
-                                                                              nb_heuristic_calls,
-                                                                              depth + 1,
-                                                                              target_depth,
-                                                                              next_depth);
-                    if is_sucess
+                    let solution_length = Self::solve_iterative_deepening_Astar_rec(child_cube,
+                                                                                    None,
+                                                                                    path,
+                                                                                    iteration,
+                                                                                    Some(m.description),
+                                                                                    depth + 1,
+                                                                                    child_cost);
+                    if solution_length.is_some()
                     {
-                        return true;
+                        return solution_length;
                     }
                 }
                 // we did not find a solution at the given depth
-                false
+                None
             }
         }
     }
@@ -77,60 +256,278 @@ impl Cube
     /// uses an heuristic to prune branches
     /// NOTE:
     /// - this algorithm *will* find an optimal solution but it might be slow as it will try a large number of depths
-    pub fn solve_iterative_deepening_Astar<H: Heuristic>(&self, heuristic: &H) -> Vec<MoveDescription>
+    ///
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening_Astar<H: Heuristic<Self>>(&self,
+                                                               heuristic: &H)
+                                                               -> Option<Vec<MoveDescription>>
+    {
+        self.solve_iterative_deepening_Astar_timed(heuristic, Duration::MAX)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// uses an heuristic to prune branches
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_iterative_deepening_Astar_timed<H: Heuristic<Self>>(&self,
+                                                                     heuristic: &H,
+                                                                     timeout: Duration)
+                                                                     -> Option<Vec<MoveDescription>>
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let settings = SearchSettings { timeout, ..SearchSettings::new() };
+        let (path, stats) = self.iterative_deepening_Astar(heuristic, &moves, settings, &StdoutSink);
+        print_result(path, &stats, timeout, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// in the quarter turn metric (no 180° turns)
+    /// NOTE: any heuristic built in the half turn metric is admissible in the quarter turn metric
+    /// (but heuristics built in the quarter turn metric are stronger)
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH_QTM` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening_Astar_qtm<H: Heuristic<Self>>(&self,
+                                                                   heuristic: &H)
+                                                                   -> Option<Vec<MoveDescription>>
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves_qtm();
+        self.solve_iterative_deepening_Astar_with_moves(heuristic, &moves)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// only using the given moves (for example, only face moves to keep the centers fixed)
+    /// NOTE: a heuristic stays admissible as long as it was built with all the given moves (or more)
+    /// as removing moves can only increase the distance between a cube and a solved cube
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH_QTM` moves,
+    /// which can happen with a restricted set of moves (and means that the cube is not solvable with all quarter turns)
+    pub fn solve_iterative_deepening_Astar_with_moves<H: Heuristic<Self>>(
+        &self,
+        heuristic: &H,
+        moves: &[MoveOfSize<SIDE, NB_SQUARES>])
+        -> Option<Vec<MoveDescription>>
+    {
+        let settings = SearchSettings { max_length: MAX_SOLUTION_LENGTH_QTM, ..SearchSettings::new() };
+        let (path, stats) = self.iterative_deepening_Astar(heuristic, moves, settings, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH_QTM)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// uses an heuristic to prune branches
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_iterative_deepening_Astar_with_stats<H: Heuristic<Self>, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
     {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        self.iterative_deepening_Astar(heuristic, &moves, SearchSettings::new(), sink)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// the children of each cube are explored from the closest to the furthest from a solved cube (according to the heuristic)
+    /// this does not change the number of cubes expanded in the iterations that fail,
+    /// but can let the last iteration stop earlier
+    /// NOTE: on random scrambles, it does not reliably reduce the number of cubes expanded
+    /// and the cost of sorting makes it slower than `solve_iterative_deepening_Astar`
+    pub fn solve_iterative_deepening_Astar_ordered<H: Heuristic<Self>>(&self,
+                                                                       heuristic: &H)
+                                                                       -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.solve_iterative_deepening_Astar_ordered_with_stats(heuristic, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// exploring the most promising children first, see `solve_iterative_deepening_Astar_ordered`
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_iterative_deepening_Astar_ordered_with_stats<H: Heuristic<Self>, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let settings = SearchSettings { is_ordered: true, ..SearchSettings::new() };
+        self.iterative_deepening_Astar(heuristic, &moves, settings, sink)
+    }
+
+    /// solves the given cube by trying cost one after the other until it finds a solved cube
+    /// returns the cheapest solution according to the given costs instead of the shortest one
+    /// (for example, one avoiding the slice moves which are harder to do with the fingers)
+    /// NOTE: to stay admissible, the heuristic (which counts moves) is multiplied by the cost of the cheapest move
+    /// this makes the search slower as the costs get more uneven
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening_Astar_with_costs<H: Heuristic<Self>>(&self,
+                                                                          heuristic: &H,
+                                                                          costs: &MoveCosts)
+                                                                          -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) =
+            self.solve_iterative_deepening_Astar_with_costs_and_stats(heuristic, costs, &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube with IDA*, looking for the cheapest solution according to the given costs
+    /// see `solve_iterative_deepening_Astar_with_costs`
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    /// NOTE: the depths reported are costs
+    pub fn solve_iterative_deepening_Astar_with_costs_and_stats<H: Heuristic<Self>, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        costs: &MoveCosts,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let settings = SearchSettings { costs: *costs, ..SearchSettings::new() };
+        self.iterative_deepening_Astar(heuristic, &moves, settings, sink)
+    }
+
+    /// solves the given cube with IDA*, using a heuristic chosen at runtime
+    /// see `solve_iterative_deepening_Astar`
+    pub fn solve_iterative_deepening_Astar_dyn(&self,
+                                               heuristic: &dyn DynHeuristic<Self>)
+                                               -> Option<Vec<MoveDescription>>
+    {
+        self.solve_iterative_deepening_Astar(&DynHeuristicRef(heuristic))
+    }
+
+    /// returns the length of an optimal solution of the cube, that is its actual distance to a solved cube
+    /// (which can be smaller than the number of moves used to scramble it as moves can cancel out)
+    /// NOTE: the heuristic should not overestimate distances (see `Heuristic::overestimation_factor`)
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn optimal_distance<H: Heuristic<Self>>(&self, heuristic: &H) -> Option<u8>
+    {
+        let (_path, stats) = self.solve_iterative_deepening_Astar_with_stats(heuristic, &NullSink);
+        stats.path_length.map(|length| length as u8)
+    }
+
+    /// solves the given cube with IDA*, only looking for solutions of at most `max_depth` moves
+    /// returns None, once all shorter paths have been ruled out, if the cube cannot be solved in `max_depth` moves
+    /// useful to answer requests such as "give me a solution within N moves" quickly
+    /// NOTE: with a heuristic that overestimates distances, a solution might be missed (but none will be too long)
+    pub fn solve_depth_limited<H: Heuristic<Self>>(&self,
+                                                   heuristic: &H,
+                                                   max_depth: usize)
+                                                   -> Option<Vec<MoveDescription>>
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let settings = SearchSettings { max_length: max_depth, ..SearchSettings::new() };
+        let (path, _stats) = self.iterative_deepening_Astar(heuristic, &moves, settings, &NullSink);
+        path.filter(|path| path.len() <= max_depth)
+    }
+
+    /// solves the given cube with IDA* and returns the solution along with all the cubes visited by it
+    /// (starting with the cube itself and ending with a solved cube), which is useful to animate a resolution
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_with_trace<H: Heuristic<Self>>(&self,
+                                                heuristic: &H)
+                                                -> Option<(Vec<MoveDescription>, Vec<Self>)>
+    {
+        let (path, _stats) = self.solve_iterative_deepening_Astar_with_stats(heuristic, &NullSink);
+        let path = path?;
+        let cubes = self.trace_path(&path);
+        Some((path, cubes))
+    }
+
+    /// returns an optimal path going from the cube to the target (up to a rotation of the whole cube)
+    /// this is done by relabeling the colors such that the target becomes solved and then solving the cube with IDA*
+    /// useful to go from one pattern to another
+    /// returns None if one of the cubes is invalid or if the target cannot be reached in `MAX_SOLUTION_LENGTH` moves
+    pub fn path_between<H: Heuristic<Self>>(&self,
+                                            target: &Self,
+                                            heuristic: &H)
+                                            -> Option<Vec<MoveDescription>>
+    {
+        let relative_cube = self.relative_to(target)?;
+        let (path, _stats) = relative_cube.solve_iterative_deepening_Astar_with_stats(heuristic, &NullSink);
+        path
+    }
+
+    /// iterative deepening A*, using the given moves
+    /// returns None if no solution was found in less than `settings.timeout` or in less than `settings.max_length` moves
+    fn iterative_deepening_Astar<H: Heuristic<Self>, S: ProgressSink, K: Ord>(
+        &self,
+        heuristic: &H,
+        moves: &[MoveOfSize<SIDE, NB_SQUARES>],
+        settings: SearchSettings<Self, K>,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
+        let SearchSettings { mut transpositions, is_ordered, costs, max_length, timeout } = settings;
         // used to time the computation
         let timer = Stopwatch::start_new();
         let mut nb_cube_expanded = 0;
         let mut nb_heuristic_calls = 0;
-        // all moves that can be applied to a cube
-        let moves = Move::all_moves();
+        // used to prune redundant moves
+        let available_moves = available_moves(moves);
+        // a heuristic that overestimates distances might need to search deeper to find a solution of length `max_length`
+        // the depths being costs, a solution of length `max_length` might cost up to `max_cost` per move
+        let max_length_cost = max_length * costs.max_cost();
+        let max_target_depth = ((max_length_cost as f32) * heuristic.overestimation_factor()).ceil() as usize;
         let dummy_move = MoveDescription { kind: MoveKind::Front, amplitude: Amplitude::Clockwise };
 
         let mut target_depth = 0;
-        let mut path = Vec::new();
+        // we let the path be one element longer than the length as our research will be one ahead
+        let mut path = vec![dummy_move];
         loop
         {
             // tries to find a solution at the given depth
-            let cube = self.clone();
-            let mut next_depth = usize::MAX; // upper bound on the depth of the optimal solution
-            let is_solved = Cube::solve_iterative_deepening_Astar_rec(cube,
-                                                                      &mut path,
-                                                                      &moves,
-                                                                      heuristic,
-                                                                      &mut nb_cube_expanded,
-                                                                      &mut nb_heuristic_calls,
-                                                                      0,
-                                                                      target_depth,
-                                                                      &mut next_depth);
-
-            // checks if we reached the target
-            if is_solved
+            // depths seen during the previous iterations are not comparable with the current one
+            transpositions.clear();
+            let mut iteration = Iteration { moves,
+                                            heuristic,
+                                            available_moves: &available_moves,
+                                            costs: &costs,
+                                            is_ordered,
+                                            timer: &timer,
+                                            timeout,
+                                            target_depth,
+                                            // upper bound on the depth of the optimal solution
+                                            next_depth: usize::MAX,
+                                            transpositions: &mut transpositions,
+                                            nb_cube_expanded: 0,
+                                            nb_heuristic_calls: 0 };
+            let solution_length = Self::solve_iterative_deepening_Astar_rec(self.clone(),
+                                                                            None,
+                                                                            &mut path,
+                                                                            &mut iteration,
+                                                                            None,
+                                                                            0,
+                                                                            0);
+            let next_depth = iteration.next_depth;
+            nb_cube_expanded += iteration.nb_cube_expanded;
+            nb_heuristic_calls += iteration.nb_heuristic_calls;
+
+            // checks if we reached the target, ran out of time or proved that there is no solution short enough
+            let is_solved = solution_length.is_some();
+            let next_target_depth = if next_depth == usize::MAX { target_depth + 1 } else { next_depth };
+            if is_solved || (timer.elapsed() > timeout) || (next_target_depth > max_target_depth)
             {
-                // removes the, potentially, one element too many at the end of the path
-                path.truncate(target_depth);
-                // displays the result
-                println!("Done! Found a path of length {} in {:?} ({} cubes expanded / {} heuristic call)",
-                         target_depth,
-                         timer.elapsed(),
-                         nb_cube_expanded,
-                         nb_heuristic_calls);
-                println!("Path: {:?}", path);
-                return path;
+                // removes the elements, past the end of the solution, that were only used by the search
+                path.truncate(solution_length.unwrap_or(0));
+                let stats = SolveStats { path_length: solution_length,
+                                         nb_cube_expanded,
+                                         nb_heuristic_calls,
+                                         nb_transposition_hits: transpositions.nb_hits,
+                                         max_depth_reached: target_depth,
+                                         elapsed: timer.elapsed() };
+                return (is_solved.then_some(path), stats);
             }
             else
             {
-                // display information on the run
-                println!("Iterative deepening A*: did distance {} in {:?} ({} cubes expanded / {} heuristic call)", target_depth, timer.elapsed(), nb_cube_expanded, nb_heuristic_calls);
+                // reports information on the run
+                sink.report(ProgressEvent::DepthCompleted { solver: "Iterative deepening A*",
+                                                            depth: target_depth,
+                                                            nb_cube_expanded,
+                                                            nb_heuristic_calls,
+                                                            elapsed: timer.elapsed() });
                 // updates the target depth
-                if next_depth == usize::MAX
-                {
-                    target_depth += 1;
-                }
-                else
-                {
-                    target_depth = next_depth;
-                }
+                target_depth = next_target_depth;
                 // increases the size of the path for the next iteration
                 // we let the path be one element longer than the length as our research will be one ahead
                 while path.len() <= target_depth
@@ -141,119 +538,380 @@ impl Cube
         }
     }
 
+    /// adds all the solutions of length `target_depth` to `solutions`
+    /// stops early once `max_nb_solutions` solutions have been found
+    /// NOTE: we only prune consecutive moves of the same kind (they never appear in an optimal solution)
+    /// as paths that only differ by the order of commuting moves are distinct solutions
+    fn solve_all_optimal_rec<H: Heuristic<Self>>(cube: Self,
+                                                 path: &mut Vec<MoveDescription>,
+                                                 moves: &[MoveOfSize<SIDE, NB_SQUARES>],
+                                                 heuristic: &H,
+                                                 target_depth: usize,
+                                                 max_nb_solutions: usize,
+                                                 solutions: &mut Vec<Vec<MoveDescription>>)
+    {
+        let minimum_final_depth = path.len() + heuristic.optimistic_distance_to_solved(&cube) as usize;
+        if (minimum_final_depth > target_depth) || (solutions.len() >= max_nb_solutions)
+        {
+            // there is no solution in this branch or we have enough solutions
+        }
+        else if path.len() == target_depth
+        {
+            // we reached the target depth, saves the path if it is a solution
+            if cube.is_solved()
+            {
+                solutions.push(path.clone());
+            }
+        }
+        else
+        {
+            // expands to the next depth
+            let previous_kind = path.last().map(|m| m.kind);
+            for m in moves.iter().filter(|m| Some(m.description.kind) != previous_kind)
+            {
+                let child_cube = cube.apply_move(m);
+                path.push(m.description);
+                Self::solve_all_optimal_rec(child_cube,
+                                            path,
+                                            moves,
+                                            heuristic,
+                                            target_depth,
+                                            max_nb_solutions,
+                                            solutions);
+                path.pop();
+            }
+        }
+    }
+
+    /// returns all the optimal solutions of the cube (or only the first `max_nb_solutions` if it is not None)
+    /// this is done by finding the length of an optimal solution with IDA*
+    /// then exploring all the paths of that length
+    /// NOTE: each path is returned once, paths that only differ by the order of commuting moves are all returned
+    /// WARNING: the heuristic needs to be admissible for the solutions to be optimal
+    /// progress is reported to the given sink
+    ///
+    /// returns an empty vector if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_all_optimal<H: Heuristic<Self>, S: ProgressSink>(&self,
+                                                                  heuristic: &H,
+                                                                  max_nb_solutions: Option<usize>,
+                                                                  sink: &S)
+                                                                  -> Vec<Vec<MoveDescription>>
+    {
+        // finds the length of the optimal solutions
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let (path, _stats) = self.iterative_deepening_Astar(heuristic, &moves, SearchSettings::new(), sink);
+        match path
+        {
+            None => Vec::new(),
+            Some(path) =>
+            {
+                // collects all the paths of optimal length
+                let timer = Stopwatch::start_new();
+                let mut solutions = Vec::new();
+                Self::solve_all_optimal_rec(self.clone(),
+                                            &mut Vec::new(),
+                                            &moves,
+                                            heuristic,
+                                            path.len(),
+                                            max_nb_solutions.unwrap_or(usize::MAX),
+                                            &mut solutions);
+                sink.report(ProgressEvent::AllPathsFound { solver: "Iterative deepening A*",
+                                                           nb_paths: solutions.len(),
+                                                           path_length: path.len(),
+                                                           elapsed: timer.elapsed() });
+                solutions
+            }
+        }
+    }
+
     /// solves the given cube by trying depth one after the other until it finds a solved cube
     /// uses an heuristic to prune branches
     /// NOTE:
     /// - this algorithm *will* find an optimal solution but it might be slow as it will try a large number of depths
-    pub fn solve_iterative_deepening_Astar_parallel<H: Heuristic + Sync>(&self,
-                                                                         heuristic: &H)
-                                                                         -> Vec<MoveDescription>
+    ///
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening_Astar_parallel<H: Heuristic<Self> + Sync>(&self,
+                                                                               heuristic: &H)
+                                                                               -> Option<Vec<MoveDescription>>
     {
-        // used to time the computation
-        let timer = Stopwatch::start_new();
-        // all moves that can be applied to a cube
-        let moves = Move::all_moves();
-        let dummy_move = MoveDescription { kind: MoveKind::Front, amplitude: Amplitude::Clockwise };
-        let mut path = Vec::new();
+        self.solve_iterative_deepening_Astar_parallel_timed(heuristic, Duration::MAX)
+    }
 
-        // tries a depth 0
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// uses an heuristic to prune branches
+    /// returns None if no solution was found in less than `timeout`
+    pub fn solve_iterative_deepening_Astar_parallel_timed<H: Heuristic<Self> + Sync>(
+        &self,
+        heuristic: &H,
+        timeout: Duration)
+        -> Option<Vec<MoveDescription>>
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        let (path, stats) = self.iterative_deepening_Astar_parallel(heuristic,
+                                                                    &moves,
+                                                                    MAX_SOLUTION_LENGTH,
+                                                                    timeout,
+                                                                    &StdoutSink);
+        print_result(path, &stats, timeout, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// in the quarter turn metric (no 180° turns)
+    /// returns None if the cube cannot be solved in `MAX_SOLUTION_LENGTH_QTM` moves (meaning that it is not solvable)
+    pub fn solve_iterative_deepening_Astar_parallel_qtm<H: Heuristic<Self> + Sync>(
+        &self,
+        heuristic: &H)
+        -> Option<Vec<MoveDescription>>
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves_qtm();
+        let (path, stats) = self.iterative_deepening_Astar_parallel(heuristic,
+                                                                    &moves,
+                                                                    MAX_SOLUTION_LENGTH_QTM,
+                                                                    Duration::MAX,
+                                                                    &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH_QTM)
+    }
+
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// uses an heuristic to prune branches
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_iterative_deepening_Astar_parallel_with_stats<H: Heuristic<Self> + Sync, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        let moves = MoveOfSize::<SIDE, NB_SQUARES>::all_moves();
+        self.iterative_deepening_Astar_parallel(heuristic, &moves, MAX_SOLUTION_LENGTH, Duration::MAX, sink)
+    }
+
+    /// solves a batch of cubes in parallel, one cube per thread, with `solve_iterative_deepening_Astar`
+    /// returns the statistics of each resolution, in the order of the cubes, instead of displaying them
+    /// NOTE: the heuristic calls are counted by each resolution,
+    /// the counts are thus per cube even if the heuristic is wrapped in a shared `CounterHeuristic`
+    pub fn solve_many<H: Heuristic<Self> + Sync>(cubes: &[Self], heuristic: &H) -> Vec<SolveStats>
+    {
+        cubes.par_iter()
+             .map(|cube| cube.solve_iterative_deepening_Astar_with_stats(heuristic, &NullSink).1)
+             .collect()
+    }
+
+    /// parallel iterative deepening A*, using the given moves
+    /// returns None if no solution was found in less than `timeout` or in less than `max_length` moves
+    fn iterative_deepening_Astar_parallel<H: Heuristic<Self> + Sync, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        moves: &[MoveOfSize<SIDE, NB_SQUARES>],
+        max_length: usize,
+        timeout: Duration,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
         if self.is_solved()
         {
-            println!("Done! Found a path of length 0 in {:?} (0 cubes expanded / 0 heuristic call)",
-                     timer.elapsed());
-            println!("Path: {:?}", path);
-            return path;
+            return already_solved();
         }
+        // used to time the computation
+        let timer = Stopwatch::start_new();
+        // used to prune redundant moves
+        let available_moves = available_moves(moves);
+        // all moves cost 1 as the depths of the threads are shifted by the first move
+        let costs = MoveCosts::uniform();
+        // a heuristic that overestimates distances might need to search deeper to find a solution of length `max_length`
+        let max_target_depth = ((max_length as f32) * heuristic.overestimation_factor()).ceil() as usize;
+        let dummy_move = MoveDescription { kind: MoveKind::Front, amplitude: Amplitude::Clockwise };
+        let mut path = Vec::new();
 
         // does a single expansion and uses the result as our starting point
-        let cubes_paths: Vec<(Cube, MoveDescription)> = moves.iter()
-                                                                  .map(|m| {
-                                                                      let cube = self.apply_move(m);
-                                                                      (cube, m.description)
-                                                                  })
-                                                                  .collect();
-        path.push(dummy_move);
+        let cubes_paths: Vec<(Self, MoveDescription)> =
+            moves.iter().map(|m| (self.apply_move(m), m.description)).collect();
         let nb_cube_expanded = AtomicUsize::new(1);
         let nb_heuristic_calls = AtomicUsize::new(0);
         let mut target_depth = 1;
+        // we let the path be one element longer than the length as our research will be one ahead
+        while path.len() <= target_depth
+        {
+            path.push(dummy_move);
+        }
         loop
         {
             // tries to find a solution at the given depth
             // does it in parallel over the once expanded cubes
             let next_depth = AtomicUsize::new(usize::MAX);
-            let path_option =
-                cubes_paths.par_iter().find_map_any(|(cube, description)| {
-                                          // increases the size of the path for the iteration
-                                          // we let the path be one element longer than the length as our research will be one ahead
-                                          let shifted_target_depth = target_depth - 1;
-                                          let cube = cube.clone();
-                                          let mut path_thread = path.clone();
-                                          path_thread[0] = *description;
-                                          let mut next_depth_thread = usize::MAX; // upper bound on the depth of the optimal solution
-                                          let mut nb_cube_expanded_thread = 0;
-                                          let mut nb_heuristic_calls_thread = 0;
-                                          let is_solved = Cube::solve_iterative_deepening_Astar_rec(cube,
-                                                                                                            &mut path_thread[1..],
-                                                                                                            &moves,
-                                                                                                            heuristic,
-                                                                                                            &mut nb_cube_expanded_thread,
-                                                                                                            &mut nb_heuristic_calls_thread,
-                                                                                                         0,
-                                                                                                       shifted_target_depth,
-                                                                                                        &mut next_depth_thread);
-                                          // updates the counters
-                                          let shifted_next_depth = if next_depth_thread == usize::MAX {target_depth+1} else {next_depth_thread + 1};
-                                          next_depth.fetch_min(shifted_next_depth, Ordering::Relaxed);
-                                          nb_cube_expanded.fetch_add(nb_cube_expanded_thread,
-                                                                     Ordering::Relaxed);
-                                          nb_heuristic_calls.fetch_add(nb_heuristic_calls_thread,
-                                                                       Ordering::Relaxed);
-                                          // returns the result if we suceeded
-                                          if is_solved
-                                          {
-                                              Some(path_thread)
-                                          }
-                                          else
-                                          {
-                                              None
-                                          }
-                                      });
+            let path_option = cubes_paths.par_iter().find_map_any(|(cube, description)| {
+                // the first move is not part of the path explored by the thread
+                let mut path_thread = path.clone();
+                path_thread[0] = *description;
+                let mut transpositions = TranspositionTable::disabled();
+                let mut iteration = Iteration { moves,
+                                                heuristic,
+                                                available_moves: &available_moves,
+                                                costs: &costs,
+                                                is_ordered: false,
+                                                timer: &timer,
+                                                timeout,
+                                                target_depth: target_depth - 1,
+                                                // upper bound on the depth of the optimal solution
+                                                next_depth: usize::MAX,
+                                                transpositions: &mut transpositions,
+                                                nb_cube_expanded: 0,
+                                                nb_heuristic_calls: 0 };
+                let solution_length = Self::solve_iterative_deepening_Astar_rec(cube.clone(),
+                                                                                None,
+                                                                                &mut path_thread[1..],
+                                                                                &mut iteration,
+                                                                                Some(*description),
+                                                                                0,
+                                                                                0);
+                // updates the counters, shifting the depths by the first move
+                let shifted_next_depth = if iteration.next_depth == usize::MAX
+                {
+                    target_depth + 1
+                }
+                else
+                {
+                    iteration.next_depth + 1
+                };
+                next_depth.fetch_min(shifted_next_depth, Ordering::Relaxed);
+                nb_cube_expanded.fetch_add(iteration.nb_cube_expanded, Ordering::Relaxed);
+                nb_heuristic_calls.fetch_add(iteration.nb_heuristic_calls, Ordering::Relaxed);
+                // returns the result if we suceeded
+                solution_length.map(|length| {
+                                   path_thread.truncate(length + 1);
+                                   path_thread
+                               })
+            });
             let next_depth = next_depth.into_inner();
 
-            // checks if we reached the target
+            // checks if we reached the target or ran out of time
+            let stats = SolveStats { path_length: path_option.as_ref().map(|_| target_depth),
+                                     nb_cube_expanded: nb_cube_expanded.load(Ordering::Relaxed),
+                                     nb_heuristic_calls: nb_heuristic_calls.load(Ordering::Relaxed),
+                                     nb_transposition_hits: 0,
+                                     max_depth_reached: target_depth,
+                                     elapsed: timer.elapsed() };
             match path_option
             {
                 Some(mut path) =>
                 {
                     // removes the, potentially, one element too many at the end of the path
                     path.truncate(target_depth);
-                    // displays the result
-                    println!("Done! Found a path of length {} in {:?} ({} cubes expanded / {} heuristic call)",
-                            target_depth, 
-                            timer.elapsed(),
-                            nb_cube_expanded.load(Ordering::Relaxed),
-                            nb_heuristic_calls.load(Ordering::Relaxed));
-                    println!("Path: {:?}", path);
-                    return path;
+                    return (Some(path), stats);
+                }
+                None if (timer.elapsed() > timeout) || (next_depth > max_target_depth) =>
+                {
+                    // we ran out of time or there is no solution short enough
+                    return (None, stats);
                 }
                 None =>
                 {
-                    // display information on the run
-                    println!("Iterative deepening A*: did distance {} in {:?} ({} cubes expanded / {} heuristic call)", 
-                            target_depth, 
-                            timer.elapsed(), 
-                            nb_cube_expanded.load(Ordering::Relaxed), 
-                            nb_heuristic_calls.load(Ordering::Relaxed));
+                    // reports information on the run
+                    sink.report(ProgressEvent::DepthCompleted { solver: "Iterative deepening A*",
+                                                                depth: target_depth,
+                                                                nb_cube_expanded: stats.nb_cube_expanded,
+                                                                nb_heuristic_calls: stats.nb_heuristic_calls,
+                                                                elapsed: stats.elapsed });
                     // updates the target depth
                     target_depth = next_depth;
-                                    // increases the size of the path for the next iteration
-                // we let the path be one element longer than the length as our research will be one ahead
-                while path.len() <= target_depth
-                {
-                    path.push(dummy_move);
-                }
+                    // increases the size of the path for the next iteration
+                    // we let the path be one element longer than the length as our research will be one ahead
+                    while path.len() <= target_depth
+                    {
+                        path.push(dummy_move);
+                    }
                 }
             }
         }
     }
 }
+
+impl Cube
+{
+    /// solves the given cube by trying depth one after the other until it finds a solved cube
+    /// skipping the cubes that have already been explored, during the current iteration, with at least as many moves left
+    /// up to `max_nb_cubes` cubes are remembered (each taking a few dozen bytes), 0 gives back plain IDA*
+    /// trading the constant memory use of IDA* for a reduction in the number of cubes expanded
+    pub fn solve_iterative_deepening_Astar_with_transpositions<H: Heuristic>(&self,
+                                                                            heuristic: &H,
+                                                                            max_nb_cubes: usize)
+                                                                            -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.solve_iterative_deepening_Astar_with_transpositions_and_stats(heuristic,
+                                                                                                 max_nb_cubes,
+                                                                                                 &StdoutSink);
+        print_result(path, &stats, Duration::MAX, MAX_SOLUTION_LENGTH)
+    }
+
+    /// solves the given cube with IDA*, using a transposition table storing up to `max_nb_cubes` cubes
+    /// returns statistics on the run (including the number of transposition hits) instead of displaying them
+    /// and reports progress to the given sink
+    pub fn solve_iterative_deepening_Astar_with_transpositions_and_stats<H: Heuristic, S: ProgressSink>(
+        &self,
+        heuristic: &H,
+        max_nb_cubes: usize,
+        sink: &S)
+        -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        let moves = Move::all_moves();
+        let transpositions = TranspositionTable::new(max_nb_cubes, Cube::to_identifier);
+        let settings = SearchSettings { transpositions,
+                                       is_ordered: false,
+                                       costs: MoveCosts::uniform(),
+                                       max_length: MAX_SOLUTION_LENGTH,
+                                       timeout: Duration::MAX };
+        self.iterative_deepening_Astar(heuristic, &moves, settings, sink)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::solver::heuristic::StickerHeuristic;
+
+    /// a cube scrambled with 8 moves cannot be solved in 5 moves, the search gives up instead of hanging
+    #[test]
+    fn depth_limited_gives_up()
+    {
+        let cube = Cube::solved().scramble_seeded(8, 0);
+        assert!(cube.solve_depth_limited(&StickerHeuristic, 5).is_none());
+    }
+
+    /// a cube that can be solved within the limit is solved
+    #[test]
+    fn depth_limited_solves()
+    {
+        let cube = Cube::solved().scramble_seeded(3, 0);
+        let path = cube.solve_depth_limited(&StickerHeuristic, 5).unwrap();
+        assert!(path.len() <= 3);
+        assert!(cube.apply_path(&path).is_solved());
+    }
+
+    /// the optimal distance is the length of an optimal solution, which can be shorter than the scramble
+    #[test]
+    fn optimal_distance_matches_optimal_solution()
+    {
+        assert_eq!(Cube::solved().optimal_distance(&StickerHeuristic), Some(0));
+        let cube = Cube::solved().apply_path(&Cube::parse_path("R U U' R").unwrap());
+        assert_eq!(cube.optimal_distance(&StickerHeuristic), Some(1));
+        for seed in 0..5
+        {
+            let cube = Cube::solved().scramble_seeded(3, seed);
+            let (path, _) = cube.solve_breath_first_search_with_stats(&NullSink);
+            assert_eq!(cube.optimal_distance(&StickerHeuristic), path.map(|path| path.len() as u8));
+        }
+    }
+
+    /// all optimal solutions are returned, each of them solves the cube
+    #[test]
+    fn all_optimal_solutions()
+    {
+        let cube = Cube::solved().apply_path(&Cube::parse_path("R U").unwrap());
+        let solutions = cube.solve_all_optimal(&StickerHeuristic, None, &NullSink);
+        assert!(solutions.contains(&Cube::parse_path("U' R'").unwrap()));
+        assert!(solutions.iter().all(|path| path.len() == 2 && cube.apply_path(path).is_solved()));
+        let solutions = cube.solve_all_optimal(&StickerHeuristic, Some(1), &NullSink);
+        assert_eq!(solutions.len(), 1);
+    }
+}
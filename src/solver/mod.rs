@@ -1,5 +1,61 @@
 pub mod heuristic;
 pub mod best_first_search;
 pub mod breath_first_search;
+pub mod bidirectional_breath_first_search;
 pub mod iterative_deepening;
 pub mod iterative_deepening_Astar;
+pub mod Astar;
+pub mod two_phase;
+pub mod dataset;
+mod stats;
+mod costs;
+mod progress;
+mod cubeset;
+mod transposition;
+pub use stats::SolveStats;
+pub use costs::MoveCosts;
+pub use progress::{ProgressSink, ProgressEvent, StdoutSink, NullSink};
+use std::time::Duration;
+use stopwatch::Stopwatch;
+use crate::cube::Metric;
+use crate::cube::moves::MoveDescription;
+
+/// maximum number of moves needed to solve any cube (God's number, in the half turn metric)
+/// solvers stop searching, returning None, once they have proven that there is no solution that short
+/// which only happens if the cube cannot be solved
+pub const MAX_SOLUTION_LENGTH: usize = 20;
+
+/// maximum number of moves needed to solve any cube in the quarter turn metric
+pub const MAX_SOLUTION_LENGTH_QTM: usize = 26;
+
+/// returns the maximum number of moves needed to solve any cube in the given metric
+fn max_solution_length(metric: Metric) -> usize
+{
+    match metric
+    {
+        Metric::HalfTurn => MAX_SOLUTION_LENGTH,
+        Metric::QuarterTurn => MAX_SOLUTION_LENGTH_QTM
+    }
+}
+
+/// number of cubes expanded between two checks of the timeout
+const TIMEOUT_CHECK_PERIOD: usize = 1000;
+
+/// returns true if the solver has run for longer than `timeout`
+/// as getting the time has a cost, we only check it once every `TIMEOUT_CHECK_PERIOD` cubes expanded
+fn is_out_of_time(timer: &Stopwatch, timeout: Duration, nb_cube_expanded: usize) -> bool
+{
+    nb_cube_expanded.is_multiple_of(TIMEOUT_CHECK_PERIOD) && (timer.elapsed() > timeout)
+}
+
+/// result of the solvers for a cube that is already solved: an empty path, found without doing any work
+fn already_solved() -> (Option<Vec<MoveDescription>>, SolveStats)
+{
+    let stats = SolveStats { path_length: Some(0),
+                             nb_cube_expanded: 0,
+                             nb_heuristic_calls: 0,
+                             nb_transposition_hits: 0,
+                             max_depth_reached: 0,
+                             elapsed: Duration::ZERO };
+    (Some(Vec::new()), stats)
+}
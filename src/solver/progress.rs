@@ -0,0 +1,113 @@
+use std::time::Duration;
+use progressing::{mapping::Bar, Baring};
+
+/// events reported by the solvers and the heuristic builders while they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent
+{
+    /// a solver is done exploring the given depth
+    DepthCompleted
+    {
+        solver: &'static str,
+        depth: usize,
+        nb_cube_expanded: usize,
+        nb_heuristic_calls: usize,
+        elapsed: Duration
+    },
+    /// a solver found a new path (which might not be its final result)
+    PathFound
+    {
+        solver: &'static str,
+        path_length: usize,
+        elapsed: Duration
+    },
+    /// a solver enumerating solutions found all the paths of the given length
+    AllPathsFound
+    {
+        solver: &'static str,
+        nb_paths: usize,
+        path_length: usize,
+        elapsed: Duration
+    },
+    /// a table heuristic has been filled up to the given depth
+    TableDepthCompleted
+    {
+        depth: usize,
+        nb_known_cubes: usize,
+        table_size: usize,
+        elapsed: Duration
+    },
+    /// a table heuristic is complete
+    TableCompleted
+    {
+        max_distance: usize,
+        table_size: usize,
+        elapsed: Duration
+    },
+    /// number of entries of a complete table heuristic holding the given distance
+    /// reported once per distance present in the table, after `TableCompleted`
+    TableDistanceCount
+    {
+        distance: usize,
+        nb_entries: usize,
+        table_size: usize
+    }
+}
+
+/// receives the progress of the solvers and of the heuristic builders
+/// this lets users display progress however they see fit
+pub trait ProgressSink
+{
+    fn report(&self, event: ProgressEvent);
+}
+
+/// displays the progress on the standard output
+pub struct StdoutSink;
+
+impl ProgressSink for StdoutSink
+{
+    fn report(&self, event: ProgressEvent)
+    {
+        match event
+        {
+            ProgressEvent::DepthCompleted { solver, depth, nb_cube_expanded, nb_heuristic_calls, elapsed } =>
+            {
+                println!("{}: did distance {} in {:?} ({} cubes expanded / {} heuristic call)",
+                         solver, depth, elapsed, nb_cube_expanded, nb_heuristic_calls)
+            }
+            ProgressEvent::PathFound { solver, path_length, elapsed } =>
+            {
+                println!("{}: found a path of length {} in {:?}", solver, path_length, elapsed)
+            }
+            ProgressEvent::AllPathsFound { solver, nb_paths, path_length, elapsed } =>
+            {
+                println!("{}: found {} optimal paths of length {} in {:?}",
+                         solver, nb_paths, path_length, elapsed)
+            }
+            ProgressEvent::TableDepthCompleted { depth, nb_known_cubes, table_size, elapsed } =>
+            {
+                let mut progress_bar = Bar::with_range(0, table_size);
+                progress_bar.set(nb_known_cubes);
+                println!("Table: did distance {} {} in {:?}", depth, progress_bar, elapsed)
+            }
+            ProgressEvent::TableCompleted { max_distance, table_size, elapsed } =>
+            {
+                println!("Table done! (maximum distance:{} table size:{} computing time:{:?})",
+                         max_distance, table_size, elapsed)
+            }
+            ProgressEvent::TableDistanceCount { distance, nb_entries, table_size } =>
+            {
+                let percentage = 100. * (nb_entries as f64) / (table_size as f64);
+                println!("Table: {} entries ({:.2}%) at distance {}", nb_entries, percentage, distance)
+            }
+        }
+    }
+}
+
+/// ignores all progress
+pub struct NullSink;
+
+impl ProgressSink for NullSink
+{
+    fn report(&self, _event: ProgressEvent) {}
+}
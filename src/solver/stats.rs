@@ -0,0 +1,80 @@
+use std::fmt;
+use std::time::Duration;
+use crate::cube::moves::MoveDescription;
+use crate::cube::path_to_string;
+
+/// statistics on a run of a solver
+/// lets library users benchmark the solvers without having to parse their output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveStats
+{
+    /// length of the path found, None if the solver stopped before finding one
+    pub path_length: Option<usize>,
+    pub nb_cube_expanded: usize,
+    pub nb_heuristic_calls: usize,
+    /// number of cubes skipped because they were found in a transposition table
+    pub nb_transposition_hits: usize,
+    /// deepest distance explored by the solver
+    pub max_depth_reached: usize,
+    /// time spent in the solver
+    pub elapsed: Duration
+}
+
+impl SolveStats
+{
+    /// proportion of the cubes looked up in the transposition table that were skipped
+    /// every cube about to be expanded is looked up, 0 if the solver did not use a transposition table
+    pub fn transposition_hit_rate(&self) -> f64
+    {
+        let nb_lookups = self.nb_cube_expanded + self.nb_transposition_hits;
+        if self.nb_transposition_hits == 0
+        {
+            0.
+        }
+        else
+        {
+            (self.nb_transposition_hits as f64) / (nb_lookups as f64)
+        }
+    }
+}
+
+/// displays the statistics in the format used by the solvers' logs
+impl fmt::Display for SolveStats
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f,
+               "in {:?} ({} cubes expanded / {} heuristic call",
+               self.elapsed, self.nb_cube_expanded, self.nb_heuristic_calls)?;
+        if self.nb_transposition_hits > 0
+        {
+            write!(f,
+                   " / {} transposition hits ({:.1}%)",
+                   self.nb_transposition_hits,
+                   100. * self.transposition_hit_rate())?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// displays the result of a solver and passes the path through
+/// a solver that did not find a path either ran out of time
+/// or proved that there is no path of length at most `max_length`
+pub(super) fn print_result(path: Option<Vec<MoveDescription>>,
+                           stats: &SolveStats,
+                           timeout: Duration,
+                           max_length: usize)
+                           -> Option<Vec<MoveDescription>>
+{
+    match &path
+    {
+        Some(path) =>
+        {
+            println!("Done! Found a path of length {} {}", path.len(), stats);
+            println!("Path: {}", path_to_string(path));
+        }
+        None if stats.elapsed > timeout => println!("Timeout! No path found {}", stats),
+        None => println!("Done! There is no path of length at most {} {}", max_length, stats)
+    }
+    path
+}
@@ -0,0 +1,78 @@
+use std::collections::{BTreeMap, btree_map::Entry};
+
+/// remembers the shallowest depth at which each cube has been seen during the current iteration
+/// this lets us prune cubes that have already been explored with at least as many moves left
+/// the table is bounded: once it is full, new cubes are not recorded anymore
+/// cubes of type `C` are stored as keys of type `K` (such as their identifiers)
+pub(super) struct TranspositionTable<C, K: Ord>
+{
+    depths: BTreeMap<K, usize>,
+    /// converts a cube into the key used to store it
+    key: fn(&C) -> K,
+    /// maximum number of cubes stored, 0 disables the table
+    max_nb_cubes: usize,
+    /// number of cubes that were pruned because they had already been explored
+    pub nb_hits: usize
+}
+
+impl<C, K: Ord> TranspositionTable<C, K>
+{
+    pub fn new(max_nb_cubes: usize, key: fn(&C) -> K) -> TranspositionTable<C, K>
+    {
+        TranspositionTable { depths: BTreeMap::new(), key, max_nb_cubes, nb_hits: 0 }
+    }
+
+    /// forgets all cubes, to be called at the beginning of each iteration
+    pub fn clear(&mut self)
+    {
+        self.depths.clear();
+    }
+
+    /// returns true if the cube was already seen at the same depth or shallower during this iteration
+    /// otherwise, records the cube (if there is room left) and returns false
+    pub fn is_transposition(&mut self, cube: &C, depth: usize) -> bool
+    {
+        if self.max_nb_cubes == 0
+        {
+            return false;
+        }
+        let nb_cubes = self.depths.len();
+        let is_transposition = match self.depths.entry((self.key)(cube))
+        {
+            Entry::Occupied(mut entry) =>
+            {
+                if *entry.get() <= depth
+                {
+                    true
+                }
+                else
+                {
+                    entry.insert(depth);
+                    false
+                }
+            }
+            Entry::Vacant(entry) =>
+            {
+                if nb_cubes < self.max_nb_cubes
+                {
+                    entry.insert(depth);
+                }
+                false
+            }
+        };
+        if is_transposition
+        {
+            self.nb_hits += 1;
+        }
+        is_transposition
+    }
+}
+
+impl<C> TranspositionTable<C, ()>
+{
+    /// a table that never stores any cube, for cubes that have no compact key
+    pub fn disabled() -> TranspositionTable<C, ()>
+    {
+        TranspositionTable::new(0, |_| ())
+    }
+}
@@ -0,0 +1,308 @@
+use std::time::Duration;
+use stopwatch::Stopwatch;
+use crate::cube::moves::{Move, MoveDescription, MoveKind};
+use crate::cube::Cube;
+use crate::solver::heuristic::{TwoPhaseTables, is_phase_two_move};
+use super::iterative_deepening_Astar::is_redundant;
+use super::{is_out_of_time, already_solved, MAX_SOLUTION_LENGTH};
+use super::stats::{SolveStats, print_result};
+use super::{ProgressSink, ProgressEvent, StdoutSink};
+
+/// maximum number of moves needed to solve a cube in the phase two subgroup, using only phase two moves
+const MAX_PHASE_TWO_LENGTH: usize = 18;
+
+/// state shared by all the steps of a two-phase search
+struct TwoPhaseSearch<'a, S: ProgressSink>
+{
+    /// tables used to prune both phases
+    tables: &'a TwoPhaseTables,
+    /// the search stops as soon as it finds a solution with at most this number of moves
+    max_length: usize,
+    /// shortest solution found so far
+    best_path: Option<Vec<MoveDescription>>,
+    /// used to time the computation
+    timer: Stopwatch,
+    /// the search stops, keeping its best solution so far, once it has run for longer than this duration
+    timeout: Duration,
+    nb_cube_expanded: usize,
+    nb_heuristic_calls: usize,
+    /// length of the longest path explored so far
+    max_depth_reached: usize,
+    /// receives the progress of the search
+    sink: &'a S
+}
+
+impl<'a, S: ProgressSink> TwoPhaseSearch<'a, S>
+{
+    /// explores all phase one paths of exactly `target_depth` moves that bring the cube into the phase two subgroup
+    /// then tries to finish each of them with phase two
+    /// returns true once a solution with at most `max_length` moves has been found
+    fn phase_one_rec(&mut self,
+                     cube: &Cube,
+                     path: &mut Vec<MoveDescription>,
+                     moves: &[Move],
+                     previous_kind: Option<MoveKind>,
+                     is_previous_phase_two_move: bool,
+                     target_depth: usize)
+                     -> bool
+    {
+        // lower bound on the number of steps needed to reach the phase two subgroup
+        self.nb_heuristic_calls += 1;
+        let depth = path.len();
+        let minimum_final_depth = depth + self.tables.phase_one_distance(cube) as usize;
+        if (minimum_final_depth > target_depth)
+           || is_out_of_time(&self.timer, self.timeout, self.nb_cube_expanded)
+        {
+            false
+        }
+        else if depth == target_depth
+        {
+            // we are in the phase two subgroup
+            // if the last move was a phase two move, the previous cube was already in the subgroup
+            // and this path has been finished during a previous, shorter, phase one
+            !is_previous_phase_two_move && self.phase_two(cube, path, moves)
+        }
+        else
+        {
+            // expands to the next depth
+            self.nb_cube_expanded += 1;
+            for m in moves.iter().filter(|m| !is_redundant(previous_kind, m.description.kind))
+            {
+                // applies a move
+                let child_cube = cube.apply_move(m);
+                let is_phase_two_move = is_phase_two_move(cube, &m.description);
+                // goes one depth further
+                path.push(m.description);
+                let is_sucess = self.phase_one_rec(&child_cube,
+                                                   path,
+                                                   moves,
+                                                   Some(m.description.kind),
+                                                   is_phase_two_move,
+                                                   target_depth);
+                path.pop();
+                if is_sucess
+                {
+                    return true;
+                }
+            }
+            false
+        }
+    }
+
+    /// finds the shortest way to finish the given phase one path using only phase two moves
+    /// keeps it if it improves on the best solution so far
+    /// returns true if the solution has at most `max_length` moves
+    fn phase_two(&mut self, cube: &Cube, phase_one_path: &[MoveDescription], moves: &[Move]) -> bool
+    {
+        // the solution needs to be shorter than the best solution so far to be of interest
+        // and there is no solution if phase two cannot solve the cube in `MAX_PHASE_TWO_LENGTH` moves
+        let max_depth = match &self.best_path
+        {
+            Some(best_path) => best_path.len() - 1,
+            None => usize::MAX
+        };
+        let max_depth = max_depth.min(phase_one_path.len() + MAX_PHASE_TWO_LENGTH);
+        let mut path = phase_one_path.to_vec();
+        let previous_kind = path.last().map(|m| m.kind);
+        let mut target_depth = path.len() + self.tables.phase_two_distance(cube) as usize;
+        while (target_depth <= max_depth) && (self.timer.elapsed() <= self.timeout)
+        {
+            self.max_depth_reached = self.max_depth_reached.max(target_depth);
+            let mut next_depth = usize::MAX; // upper bound on the depth of the optimal solution
+            if self.phase_two_rec(cube, &mut path, moves, previous_kind, target_depth, &mut next_depth)
+            {
+                // we found a new best solution
+                self.sink.report(ProgressEvent::PathFound { solver: "Two-phase",
+                                                            path_length: path.len(),
+                                                            elapsed: self.timer.elapsed() });
+                let is_short_enough = path.len() <= self.max_length;
+                self.best_path = Some(path);
+                return is_short_enough;
+            }
+            target_depth = next_depth;
+        }
+        false
+    }
+
+    /// returns true if it finds a solution at depth `target_depth` using only phase two moves
+    /// path will then contain the path to the solution
+    fn phase_two_rec(&mut self,
+                     cube: &Cube,
+                     path: &mut Vec<MoveDescription>,
+                     moves: &[Move],
+                     previous_kind: Option<MoveKind>,
+                     target_depth: usize,
+                     next_depth: &mut usize)
+                     -> bool
+    {
+        // lower bound on the number of steps needed to do a solve with this move
+        self.nb_heuristic_calls += 1;
+        let minimum_final_depth = path.len() + self.tables.phase_two_distance(cube) as usize;
+        match minimum_final_depth.cmp(&target_depth)
+        {
+            std::cmp::Ordering::Greater =>
+            {
+                // minimum_final_depth > target_depth
+                // updates the depth for the next round
+                if minimum_final_depth < *next_depth
+                {
+                    *next_depth = minimum_final_depth;
+                }
+                false
+            }
+            std::cmp::Ordering::Equal if cube.is_solved() =>
+            {
+                // minimum_final_depth == target_depth
+                // we found a solution
+                true
+            }
+            _ if is_out_of_time(&self.timer, self.timeout, self.nb_cube_expanded) => false,
+            _ =>
+            {
+                // minimum_final_depth <= target_depth
+                // expands to the next depth
+                self.nb_cube_expanded += 1;
+                for m in moves.iter()
+                              .filter(|m| !is_redundant(previous_kind, m.description.kind))
+                              .filter(|m| is_phase_two_move(cube, &m.description))
+                {
+                    // applies a move
+                    let child_cube = cube.apply_move(m);
+                    // goes one depth further
+                    path.push(m.description);
+                    let is_sucess = self.phase_two_rec(&child_cube,
+                                                       path,
+                                                       moves,
+                                                       Some(m.description.kind),
+                                                       target_depth,
+                                                       next_depth);
+                    if is_sucess
+                    {
+                        return true;
+                    }
+                    path.pop();
+                }
+                // we did not find a solution at the given depth
+                false
+            }
+        }
+    }
+}
+
+impl Cube
+{
+    /// solves the given cube with Kociemba's two-phase algorithm and returns the first solution found
+    /// the first phase brings the cube into the subgroup of cubes that can be solved using only
+    /// half turns and moves around the axis of the white and yellow centers,
+    /// the second phase solves the cube using only those moves
+    /// NOTE:
+    /// - this algorithm is much faster than the optimal solvers but its solution is *not* optimal
+    /// - see `solve_two_phase_bounded` to keep searching for shorter solutions
+    ///
+    /// returns None if the cube cannot be solved
+    pub fn solve_two_phase(&self, tables: &TwoPhaseTables) -> Option<Vec<MoveDescription>>
+    {
+        self.solve_two_phase_bounded(tables, usize::MAX)
+    }
+
+    /// solves the given cube with Kociemba's two-phase algorithm
+    /// keeps searching, with longer and longer phase one, until it finds a solution with at most `max_length` moves
+    /// each new solution found is shorter than the previous one
+    /// returns None if there is no solution with at most `max_length` moves
+    /// NOTE:
+    /// - if no solution is short enough, the search will go on until it has proven that the best solution is optimal
+    ///   which can be as slow as the optimal solvers
+    pub fn solve_two_phase_bounded(&self,
+                                   tables: &TwoPhaseTables,
+                                   max_length: usize)
+                                   -> Option<Vec<MoveDescription>>
+    {
+        self.solve_two_phase_timed(tables, max_length, Duration::MAX)
+    }
+
+    /// solves the given cube with Kociemba's two-phase algorithm, see `solve_two_phase_bounded`
+    /// stops after `timeout` and returns the best solution found so far, even if it is longer than `max_length`
+    /// returns None if no solution has been found in time
+    pub fn solve_two_phase_timed(&self,
+                                 tables: &TwoPhaseTables,
+                                 max_length: usize,
+                                 timeout: Duration)
+                                 -> Option<Vec<MoveDescription>>
+    {
+        let (path, stats) = self.two_phase(tables, max_length, timeout, &StdoutSink);
+        print_result(path, &stats, timeout, max_length.min(MAX_SOLUTION_LENGTH))
+    }
+
+    /// solves the given cube with Kociemba's two-phase algorithm and returns the first solution found
+    /// returns statistics on the run instead of displaying them and reports progress to the given sink
+    pub fn solve_two_phase_with_stats<S: ProgressSink>(&self,
+                                                       tables: &TwoPhaseTables,
+                                                       sink: &S)
+                                                       -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        self.two_phase(tables, usize::MAX, Duration::MAX, sink)
+    }
+
+    /// Kociemba's two-phase algorithm
+    /// returns the first solution with at most `max_length` moves
+    /// or the best solution so far if we ran out of time
+    fn two_phase<S: ProgressSink>(&self,
+                                  tables: &TwoPhaseTables,
+                                  max_length: usize,
+                                  timeout: Duration,
+                                  sink: &S)
+                                  -> (Option<Vec<MoveDescription>>, SolveStats)
+    {
+        // an already solved cube needs no search
+        if self.is_solved()
+        {
+            return already_solved();
+        }
+        // all moves that can be applied to a cube
+        let moves = Move::all_moves();
+        let mut search = TwoPhaseSearch { tables,
+                                          max_length,
+                                          best_path: None,
+                                          timer: Stopwatch::start_new(),
+                                          timeout,
+                                          nb_cube_expanded: 0,
+                                          nb_heuristic_calls: 0,
+                                          max_depth_reached: 0,
+                                          sink };
+
+        // increases the length of the first phase until we find a short enough solution
+        // a phase one longer than the best solution so far cannot lead to a shorter solution
+        // as the optimal solution is itself a phase one path, phase one never needs to be longer than God's number
+        let mut phase_one_length = 0;
+        let mut is_timeout = false;
+        while (phase_one_length <= max_length.min(MAX_SOLUTION_LENGTH))
+              && search.best_path.as_ref().is_none_or(|best_path| phase_one_length < best_path.len())
+        {
+            search.max_depth_reached = search.max_depth_reached.max(phase_one_length);
+            let mut path = Vec::new();
+            let is_solved = search.phase_one_rec(self, &mut path, &moves, None, false, phase_one_length);
+            is_timeout = search.timer.elapsed() > timeout;
+            if is_solved || is_timeout
+            {
+                break;
+            }
+            // reports information on the run
+            sink.report(ProgressEvent::DepthCompleted { solver: "Two-phase (phase one)",
+                                                        depth: phase_one_length,
+                                                        nb_cube_expanded: search.nb_cube_expanded,
+                                                        nb_heuristic_calls: search.nb_heuristic_calls,
+                                                        elapsed: search.timer.elapsed() });
+            phase_one_length += 1;
+        }
+
+        // if we ran out of time, we keep the best solution so far even if it is too long
+        let path = search.best_path.filter(|best_path| is_timeout || (best_path.len() <= max_length));
+        let stats = SolveStats { path_length: path.as_ref().map(|path| path.len()),
+                                 nb_cube_expanded: search.nb_cube_expanded,
+                                 nb_heuristic_calls: search.nb_heuristic_calls,
+                                 nb_transposition_hits: 0,
+                                 max_depth_reached: search.max_depth_reached,
+                                 elapsed: search.timer.elapsed() };
+        (path, stats)
+    }
+}